@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use criterion::{ black_box, criterion_group, criterion_main, Criterion };
+use rand::{ Rng, SeedableRng, rngs::StdRng };
+use rust_neat::{ CompiledNetwork, FeedForwardNetwork, Gene, Genome, GenomeConfig, InnovationCounter };
+use rust_neat::genome::neuron_gene;
+
+// A feed-forward genome grown by mutation to roughly the size of an evolved controller.
+fn evolved_genome() -> Genome {
+    let mut config = GenomeConfig::new(16, 4);
+    config.set_is_connected(true);
+    config.set_mutate_create_neuron(0.3);
+    config.set_mutate_create_connection(0.8);
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut counter = InnovationCounter::new(20);
+
+    let mut genome = Genome::new_with_rng(&mut counter, &config, &mut rng).unwrap();
+    for _ in 0..300 {
+        genome.mutate_with_rng(&mut counter, &config, &mut rng).unwrap();
+    }
+    genome
+}
+
+// Evaluates the genome directly, looking up every neuron and scanning the
+// connections for its inputs on each call.
+fn naive_activate(genome: &Genome, inputs: &[f64]) -> Vec<f64> {
+    fn value(genome: &Genome, neuron: u32, values: &mut HashMap<u32, f64>) -> f64 {
+        if let Some(&value) = values.get(&neuron) {
+            return value;
+        }
+        let sum: f64 = genome.iter_connections()
+            .filter(|connection| connection.is_enabled() && connection.get_neuron_out() == neuron)
+            .map(|connection| connection.get_weight() * value(genome, connection.get_neuron_in(), values))
+            .sum();
+        let value = 1.0 / (1.0 + (-sum).exp());
+        values.insert(neuron, value);
+        value
+    }
+
+    let mut values = HashMap::new();
+    let sensors = genome.iter_neurons().filter(|neuron| neuron.get_class() == neuron_gene::SENSOR);
+    for (neuron, &input) in sensors.zip(inputs.iter()) {
+        values.insert(neuron.get_innovation_number(), input);
+    }
+    genome.iter_neurons()
+        .filter(|neuron| neuron.get_class() == neuron_gene::OUTPUT)
+        .map(|neuron| value(genome, neuron.get_innovation_number(), &mut values))
+        .collect()
+}
+
+fn bench_activate(c: &mut Criterion) {
+    let genome = evolved_genome();
+    let mut rng = StdRng::seed_from_u64(7);
+    let inputs: Vec<f64> = (0..16).map(|_| rng.gen_range(-1.0, 1.0)).collect();
+    let inputs_f32: Vec<f32> = inputs.iter().map(|&input| input as f32).collect();
+
+    let mut network = FeedForwardNetwork::new(&genome).unwrap();
+    let mut compiled: CompiledNetwork<f64> = network.compile();
+    let mut compiled_f32: CompiledNetwork<f32> = network.compile();
+    let mut outputs = vec![0.0; 4];
+    let mut outputs_f32 = vec![0.0; 4];
+
+    let mut group = c.benchmark_group("activate");
+    group.bench_function("naive graph walk", |b| b.iter(|| naive_activate(&genome, black_box(&inputs))));
+    group.bench_function("FeedForwardNetwork", |b| b.iter(|| network.activate(black_box(&inputs))));
+    group.bench_function("CompiledNetwork<f64>", |b| {
+        b.iter(|| compiled.activate_into(black_box(&inputs), &mut outputs))
+    });
+    group.bench_function("CompiledNetwork<f32>", |b| {
+        b.iter(|| compiled_f32.activate_into(black_box(&inputs_f32), &mut outputs_f32))
+    });
+    group.finish();
+}
+
+fn bench_activate_batch(c: &mut Criterion) {
+    let genome = evolved_genome();
+    let mut rng = StdRng::seed_from_u64(7);
+    let dataset: Vec<Vec<f64>> = (0..256).map(|_| (0..16).map(|_| rng.gen_range(-1.0, 1.0)).collect()).collect();
+    let mut network = FeedForwardNetwork::new(&genome).unwrap();
+
+    let mut group = c.benchmark_group("dataset");
+    group.bench_function("activate per sample", |b| {
+        b.iter(|| dataset.iter().map(|input| network.activate(black_box(input))).collect::<Vec<_>>())
+    });
+    group.bench_function("activate_batch", |b| b.iter(|| network.activate_batch(black_box(&dataset))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_activate, bench_activate_batch);
+criterion_main!(benches);
@@ -0,0 +1,554 @@
+use std::fmt::Display;
+use std::fs::{ self, File };
+use std::io::{ self, BufRead, BufReader, BufWriter, Write };
+use std::path::{ Path, PathBuf };
+use std::str::FromStr;
+use std::time::{ Duration, Instant };
+use crate::{ Neat, NeatError, InnovationCounter, GenomeConfig, NeatConfig, Species };
+use crate::genome::Genome;
+use crate::genome::connection_gene::ConnectionGene;
+use crate::genome::neuron_gene::NeuronGene;
+use crate::genome::Gene;
+use crate::utils::NeatRng;
+
+const HEADER: &str = "rust_neat checkpoint 1";
+// Counts come from the file, so pre-allocation is capped to keep a corrupt count
+// from aborting the process before the missing lines are noticed.
+const MAX_PREALLOCATION: usize = 1024;
+
+pub struct Checkpointer {
+    directory: PathBuf,
+    prefix: String,
+    generation_interval: Option<usize>,
+    time_interval: Option<Duration>,
+    retain: Option<usize>,
+    last_generation: usize,
+    last_time: Instant,
+}
+
+impl Checkpointer {
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Checkpointer {
+        Checkpointer {
+            directory: directory.into(),
+            prefix: "neat-checkpoint-".to_string(),
+            generation_interval: None,
+            time_interval: None,
+            retain: None,
+            last_generation: 0,
+            last_time: Instant::now(),
+        }
+    }
+
+    pub fn set_prefix(&mut self, prefix: &str) {
+        self.prefix = prefix.to_string();
+    }
+
+    pub fn set_generation_interval(&mut self, generations: usize) {
+        self.generation_interval = Some(generations);
+    }
+
+    pub fn set_time_interval(&mut self, interval: Duration) {
+        self.time_interval = Some(interval);
+    }
+
+    pub fn set_retain(&mut self, retain: usize) {
+        self.retain = Some(retain);
+    }
+
+    pub fn get_path(&self, generation: usize) -> PathBuf {
+        self.directory.join(format!("{}{}", self.prefix, generation))
+    }
+
+    pub fn list_checkpoints(&self) -> io::Result<Vec<(usize, PathBuf)>> {
+        let mut checkpoints = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            let generation = path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(self.prefix.as_str()))
+                .and_then(|generation| generation.parse::<usize>().ok());
+            if let Some(generation) = generation {
+                checkpoints.push((generation, path));
+            }
+        }
+        checkpoints.sort();
+        Ok(checkpoints)
+    }
+
+    pub fn find_latest(&self) -> io::Result<Option<PathBuf>> {
+        Ok(self.list_checkpoints()?.pop().map(|(_, path)| path))
+    }
+
+    fn is_due(&self, generation: usize) -> bool {
+        match (self.generation_interval, self.time_interval) {
+            (None, None) => true,
+            (generations, time) => {
+                generations.is_some_and(|generations| generation - self.last_generation >= generations)
+                    || time.is_some_and(|time| self.last_time.elapsed() >= time)
+            }
+        }
+    }
+
+    fn prune(&self) -> io::Result<()> {
+        if let Some(retain) = self.retain {
+            let checkpoints = self.list_checkpoints()?;
+            let n_remove = checkpoints.len().saturating_sub(retain);
+            for (_, path) in checkpoints.into_iter().take(n_remove) {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Neat {
+    pub fn set_checkpointer(&mut self, mut checkpointer: Checkpointer) {
+        checkpointer.last_generation = self.generation;
+        checkpointer.last_time = Instant::now();
+        self.checkpointer = Some(checkpointer);
+    }
+
+    // A failed save leaves the schedule untouched, so the next generation tries again.
+    pub(crate) fn checkpoint(&mut self) -> io::Result<()> {
+        let mut checkpointer = match self.checkpointer.take() {
+            Some(checkpointer) => checkpointer,
+            None => return Ok(())
+        };
+
+        let mut result = Ok(());
+        if checkpointer.is_due(self.generation) {
+            result = self.save_checkpoint(checkpointer.get_path(self.generation))
+                .and_then(|_| checkpointer.prune());
+            if result.is_ok() {
+                checkpointer.last_generation = self.generation;
+                checkpointer.last_time = Instant::now();
+            }
+        }
+
+        self.checkpointer = Some(checkpointer);
+        result
+    }
+
+    pub fn save_checkpoint<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let temporary = path.with_extension("tmp");
+        {
+            let mut writer = BufWriter::new(File::create(&temporary)?);
+            self.write_checkpoint(&mut writer)?;
+            writer.flush()?;
+        }
+        fs::rename(temporary, path)
+    }
+
+    pub fn load_checkpoint<P: AsRef<Path>>(path: P) -> Result<Neat, NeatError> {
+        Neat::read_checkpoint(BufReader::new(File::open(path)?))
+    }
+
+    pub fn write_checkpoint<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", HEADER)?;
+        writeln!(writer, "generation {}", self.generation)?;
+        writeln!(writer, "next_species_id {}", self.next_species_id)?;
+        let state = self.rng.get_state();
+        writeln!(writer, "rng {} {} {} {}", state[0], state[1], state[2], state[3])?;
+
+        let config = &self.config;
+        writeln!(
+            writer,
+            "genome_config {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {}",
+            config.get_n_sensor(), config.get_n_output(), config.is_connected(), config.get_default_weight(),
+            config.is_weight_random(), config.get_weight_deviation(), config.get_mutate_create_connection(),
+            config.get_mutate_create_neuron(), config.get_mutate_set_weight(), config.get_mutate_update_weight(),
+            config.get_mutate_toggle_connection(), config.get_compatibility_disjoint_coefficient(),
+            config.get_compatibility_weight_coefficient(), config.get_weight_min_value(), config.get_weight_max_value(),
+            config.is_feed_forward(), config.get_mutate_bias(), config.get_bias_deviation(),
+            config.get_mutate_time_constant(), config.get_time_constant_deviation(),
+            config.get_time_constant_min_value(), config.get_time_constant_max_value(),
+            config.get_bias_min_value(), config.get_bias_max_value()
+        )?;
+        let neat_config = &self.neat_config;
+        writeln!(
+            writer,
+            "neat_config {} {} {} {} {}",
+            neat_config.get_population_size(), neat_config.get_compatibility_threshold(),
+            neat_config.get_survival_threshold(), neat_config.get_elitism(), neat_config.get_max_stagnation()
+        )?;
+
+        let mut innovations: Vec<(&(u32, u32), &u32)> = self.counter.connections_innovation_map.iter().collect();
+        innovations.sort();
+        writeln!(writer, "counter {} {}", self.counter.counter, innovations.len())?;
+        for ((neuron_in, neuron_out), innovation) in innovations {
+            writeln!(writer, "innovation {} {} {}", neuron_in, neuron_out, innovation)?;
+        }
+
+        write!(writer, "fitness {}", self.fitness.len())?;
+        for fitness in self.fitness.iter() {
+            write!(writer, " {}", fitness)?;
+        }
+        writeln!(writer)?;
+
+        match &self.champion {
+            Some((genome, fitness, generation)) => {
+                writeln!(writer, "champion {} {}", fitness, generation)?;
+                write_genome(&mut writer, genome)?;
+            },
+            None => writeln!(writer, "champion none")?
+        }
+
+        writeln!(writer, "species {}", self.species.len())?;
+        for species in self.species.iter() {
+            write!(
+                writer,
+                "species_entry {} {} {} {} {}",
+                species.get_id(), species.get_best_fitness(), species.get_last_improved(),
+                species.get_created(), species.get_members().len()
+            )?;
+            for member in species.get_members() {
+                write!(writer, " {}", member)?;
+            }
+            writeln!(writer)?;
+            write_genome(&mut writer, species.get_representative())?;
+        }
+
+        writeln!(writer, "population {}", self.population_genome.len())?;
+        for genome in self.population_genome.iter() {
+            write_genome(&mut writer, genome)?;
+        }
+        Ok(())
+    }
+
+    pub fn read_checkpoint<R: BufRead>(reader: R) -> Result<Neat, NeatError> {
+        let mut reader = CheckpointReader { lines: reader.lines(), line_number: 0 };
+
+        let header = reader.next_line()?;
+        if header.trim() != HEADER {
+            return Err(invalid_data(format!("unsupported checkpoint header {:?}", header)));
+        }
+
+        let generation = reader.expect("generation")?.parse_next()?;
+        let next_species_id = reader.expect("next_species_id")?.parse_next()?;
+        let mut line = reader.expect("rng")?;
+        let rng = NeatRng::from_state([line.parse_next()?, line.parse_next()?, line.parse_next()?, line.parse_next()?]);
+
+        let mut line = reader.expect("genome_config")?;
+        let mut config = GenomeConfig::new(line.parse_next()?, line.parse_next()?);
+        config.set_is_connected(line.parse_next()?);
+        config.set_default_weight(line.parse_next()?);
+        config.set_weight_is_random(line.parse_next()?);
+        config.set_weight_deviation(line.parse_next()?);
+        config.set_mutate_create_connection(line.parse_next()?);
+        config.set_mutate_create_neuron(line.parse_next()?);
+        config.set_mutate_set_weight(line.parse_next()?);
+        config.set_mutate_update_weight(line.parse_next()?);
+        config.set_mutate_toggle_connection(line.parse_next()?);
+        config.set_compatibility_disjoint_coefficient(line.parse_next()?);
+        config.set_compatibility_weight_coefficient(line.parse_next()?);
+        // Weight bounds and the feed-forward flag were added after the first checkpoints were written.
+        if line.has_next() {
+            config.set_weight_min_value(line.parse_next()?);
+            config.set_weight_max_value(line.parse_next()?);
+        }
+        if line.has_next() {
+            config.set_feed_forward(line.parse_next()?);
+        }
+        if line.has_next() {
+            config.set_mutate_bias(line.parse_next()?);
+            config.set_bias_deviation(line.parse_next()?);
+            config.set_mutate_time_constant(line.parse_next()?);
+            config.set_time_constant_deviation(line.parse_next()?);
+            config.set_time_constant_min_value(line.parse_next()?);
+            config.set_time_constant_max_value(line.parse_next()?);
+        }
+        if line.has_next() {
+            config.set_bias_min_value(line.parse_next()?);
+            config.set_bias_max_value(line.parse_next()?);
+        }
+
+        let mut line = reader.expect("neat_config")?;
+        let mut neat_config = NeatConfig::new(line.parse_next()?);
+        neat_config.set_compatibility_threshold(line.parse_next()?);
+        neat_config.set_survival_threshold(line.parse_next()?);
+        neat_config.set_elitism(line.parse_next()?);
+        neat_config.set_max_stagnation(line.parse_next()?);
+        config.validate()?;
+        neat_config.validate()?;
+
+        let mut line = reader.expect("counter")?;
+        let mut counter = InnovationCounter::new(line.parse_next()?);
+        let n_innovations: usize = line.parse_next()?;
+        for _ in 0..n_innovations {
+            let mut line = reader.expect("innovation")?;
+            let key = (line.parse_next()?, line.parse_next()?);
+            counter.connections_innovation_map.insert(key, line.parse_next()?);
+        }
+
+        let mut line = reader.expect("fitness")?;
+        let n_fitness: usize = line.parse_next()?;
+        let fitness = (0..n_fitness).map(|_| line.parse_next()).collect::<Result<Vec<f64>, NeatError>>()?;
+
+        let mut line = reader.expect("champion")?;
+        let champion = if line.tokens.first().map(String::as_str) == Some("none") {
+            None
+        } else {
+            let (fitness, generation) = (line.parse_next()?, line.parse_next()?);
+            Some((reader.read_genome()?, fitness, generation))
+        };
+
+        let n_species: usize = reader.expect("species")?.parse_next()?;
+        let mut species = Vec::with_capacity(n_species.min(MAX_PREALLOCATION));
+        for _ in 0..n_species {
+            let mut line = reader.expect("species_entry")?;
+            let id = line.parse_next()?;
+            let best_fitness = line.parse_next()?;
+            let last_improved = line.parse_next()?;
+            let created = line.parse_next()?;
+            let n_members: usize = line.parse_next()?;
+            let members = (0..n_members).map(|_| line.parse_next()).collect::<Result<Vec<usize>, NeatError>>()?;
+            let representative = reader.read_genome()?;
+            species.push(Species::from_parts(id, representative, members, best_fitness, last_improved, created));
+        }
+
+        let n_population: usize = reader.expect("population")?.parse_next()?;
+        let population_genome = (0..n_population).map(|_| reader.read_genome()).collect::<Result<Vec<Genome>, NeatError>>()?;
+        if !fitness.is_empty() && fitness.len() != n_population {
+            return Err(invalid_data(format!("{} fitness values for {} genomes", fitness.len(), n_population)));
+        }
+        if let Some(&member) = species.iter().flat_map(|species| species.get_members()).find(|&&member| member >= n_population) {
+            return Err(invalid_data(format!("species member {} is outside the population", member)));
+        }
+
+        Ok(Neat {
+            counter,
+            config,
+            neat_config,
+            population_genome,
+            fitness,
+            extra_data: Vec::new(),
+            species,
+            next_species_id,
+            generation,
+            champion,
+            rng,
+            reporters: Vec::new(),
+            checkpointer: None,
+        })
+    }
+}
+
+fn write_genome<W: Write>(writer: &mut W, genome: &Genome) -> io::Result<()> {
+    writeln!(writer, "genome {} {}", genome.iter_neurons().count(), genome.iter_connections().count())?;
+    for neuron in genome.iter_neurons() {
+        writeln!(
+            writer,
+            "neuron {} {} {} {}",
+            neuron.get_innovation_number(), neuron.get_class(), neuron.get_bias(), neuron.get_time_constant()
+        )?;
+    }
+    for connection in genome.iter_connections() {
+        writeln!(
+            writer,
+            "connection {} {} {} {} {}",
+            connection.get_innovation_number(), connection.get_neuron_in(), connection.get_neuron_out(),
+            connection.get_weight(), connection.is_enabled()
+        )?;
+    }
+    Ok(())
+}
+
+fn invalid_data<E: Display>(message: E) -> NeatError {
+    NeatError::Format(message.to_string())
+}
+
+struct CheckpointReader<L> {
+    lines: L,
+    line_number: usize,
+}
+
+struct CheckpointLine {
+    tokens: Vec<String>,
+    position: usize,
+    line_number: usize,
+}
+
+impl CheckpointLine {
+    fn has_next(&self) -> bool {
+        self.position < self.tokens.len()
+    }
+
+    fn parse_next<T>(&mut self) -> Result<T, NeatError>
+    where
+        T: FromStr,
+        T::Err: Display
+    {
+        let token = self.tokens.get(self.position)
+            .ok_or_else(|| invalid_data(format!("line {}: missing value", self.line_number)))?;
+        self.position += 1;
+        token.parse::<T>()
+            .map_err(|error| invalid_data(format!("line {}: invalid value {:?}: {}", self.line_number, token, error)))
+    }
+}
+
+impl<L> CheckpointReader<L>
+where
+    L: Iterator<Item = io::Result<String>>
+{
+    fn next_line(&mut self) -> Result<String, NeatError> {
+        self.line_number += 1;
+        match self.lines.next() {
+            Some(line) => Ok(line?),
+            None => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "checkpoint is truncated").into())
+        }
+    }
+
+    fn expect(&mut self, keyword: &str) -> Result<CheckpointLine, NeatError> {
+        let line = self.next_line()?;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some(found) if found == keyword => Ok(CheckpointLine {
+                tokens: tokens.map(String::from).collect(),
+                position: 0,
+                line_number: self.line_number,
+            }),
+            found => Err(invalid_data(format!(
+                "line {}: expected {:?}, found {:?}", self.line_number, keyword, found.unwrap_or("")
+            )))
+        }
+    }
+
+    fn read_genome(&mut self) -> Result<Genome, NeatError> {
+        let mut line = self.expect("genome")?;
+        let n_neurons: usize = line.parse_next()?;
+        let n_connections: usize = line.parse_next()?;
+
+        let mut neurons = Vec::with_capacity(n_neurons.min(MAX_PREALLOCATION));
+        for _ in 0..n_neurons {
+            let mut line = self.expect("neuron")?;
+            let mut neuron = NeuronGene::new(line.parse_next()?, line.parse_next()?);
+            // Older checkpoints have no bias or time constant.
+            if line.has_next() {
+                neuron.set_bias(line.parse_next()?);
+                neuron.set_time_constant(line.parse_next()?);
+            }
+            neurons.push(neuron);
+        }
+
+        let mut connections = Vec::with_capacity(n_connections.min(MAX_PREALLOCATION));
+        for _ in 0..n_connections {
+            let mut line = self.expect("connection")?;
+            let mut connection = ConnectionGene::new(
+                line.parse_next()?,
+                line.parse_next()?,
+                line.parse_next()?,
+                line.parse_next()?
+            );
+            connection.set_enabled(line.parse_next()?);
+            connections.push(connection);
+        }
+
+        Genome::from_genes(neurons, connections)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConfigError;
+
+    fn fitness(genome: &Genome) -> f64 {
+        genome.iter_connections()
+            .filter(|connection| connection.is_enabled())
+            .map(|connection| connection.get_weight().abs())
+            .sum()
+    }
+
+    fn snapshot(neat: &Neat) -> String {
+        let mut buffer = Vec::new();
+        neat.write_checkpoint(&mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_checkpoint_resume_is_exact() {
+        let mut config = GenomeConfig::new(3, 2);
+        config.set_is_connected(true);
+        config.set_mutate_create_neuron(0.3);
+        config.set_mutate_bias(0.2);
+        config.set_mutate_time_constant(0.2);
+        let mut neat = Neat::with_seed(NeatConfig::new(20), config, 7);
+
+        for _ in 0..3 {
+            neat.next_generation(&mut fitness).unwrap();
+        }
+        let saved = snapshot(&neat);
+
+        for _ in 0..3 {
+            neat.next_generation(&mut fitness).unwrap();
+        }
+
+        let mut restored = Neat::read_checkpoint(saved.as_bytes()).unwrap();
+        assert_eq!(snapshot(&restored), saved);
+
+        for _ in 0..3 {
+            restored.next_generation(&mut fitness).unwrap();
+        }
+        assert_eq!(snapshot(&restored), snapshot(&neat));
+    }
+
+    #[test]
+    fn test_checkpointer_retention() {
+        let directory = std::env::temp_dir().join(format!("rust_neat_checkpoint_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let mut neat = Neat::with_seed(NeatConfig::new(10), GenomeConfig::new(2, 1), 1);
+        let mut checkpointer = Checkpointer::new(&directory);
+        checkpointer.set_generation_interval(2);
+        checkpointer.set_retain(2);
+        neat.set_checkpointer(checkpointer);
+
+        for _ in 0..7 {
+            neat.next_generation(&mut fitness).unwrap();
+        }
+
+        let checkpointer = neat.checkpointer.as_ref().unwrap();
+        let generations: Vec<usize> = checkpointer.list_checkpoints().unwrap().into_iter().map(|(generation, _)| generation).collect();
+        assert_eq!(generations, vec![4, 6]);
+
+        let latest = checkpointer.find_latest().unwrap().unwrap();
+        let restored = Neat::load_checkpoint(latest).unwrap();
+        assert_eq!(restored.get_generation(), 6);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_checkpoint() {
+        let error = Neat::read_checkpoint("rust_neat checkpoint 1\ngeneration x\n".as_bytes()).err().unwrap();
+        assert!(matches!(error, NeatError::Format(_)));
+        assert!(error.to_string().contains("line 2"));
+
+        let truncated = Neat::read_checkpoint("rust_neat checkpoint 1\n".as_bytes()).err().unwrap();
+        assert!(matches!(truncated, NeatError::Io(ref error) if error.kind() == io::ErrorKind::UnexpectedEof));
+
+        let mut config = GenomeConfig::new(2, 1);
+        config.set_is_connected(true);
+        let saved = snapshot(&Neat::with_seed(NeatConfig::new(3), config.clone(), 1));
+        let huge = saved.replacen("genome 3 2", &format!("genome {} 0", u64::MAX), 1);
+        assert!(Neat::read_checkpoint(huge.as_bytes()).is_err());
+
+        config.set_mutate_create_neuron(2.0);
+        let invalid = snapshot(&Neat::with_seed(NeatConfig::new(3), config, 1));
+        assert!(matches!(
+            Neat::read_checkpoint(invalid.as_bytes()),
+            Err(NeatError::Config(ConfigError::Probability { name: "mutate_create_neuron", .. }))
+        ));
+    }
+
+    #[test]
+    fn test_save_error_is_returned() {
+        let directory = std::env::temp_dir().join(format!("rust_neat_missing_{}", std::process::id())).join("nested");
+        let mut neat = Neat::with_seed(NeatConfig::new(5), GenomeConfig::new(2, 1), 1);
+        neat.set_checkpointer(Checkpointer::new(&directory));
+
+        assert!(matches!(neat.next_generation(&mut fitness), Err(NeatError::Io(_))));
+        assert_eq!(neat.get_generation(), 1);
+    }
+}
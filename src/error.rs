@@ -0,0 +1,103 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use crate::genome::text::ParseGenomeError;
+use crate::genome::genome_config::ConfigError;
+use crate::genome::validate::Violation;
+use crate::ini::IniError;
+
+#[derive(Debug)]
+pub enum NeatError {
+    Io(io::Error),
+    Format(String),
+    Parse(ParseGenomeError),
+    Config(ConfigError),
+    Ini(IniError),
+    DuplicateInnovation(u32),
+    DuplicateConnection { neuron_in: u32, neuron_out: u32 },
+    DanglingNeuron { connection: u32, neuron: u32 },
+    IncompatibleGenomes { innovation: u32 },
+    FitnessCount { expected: usize, found: usize },
+    EmptyPopulation,
+    Cycle { neuron: u32 },
+    InvalidIdentifier(String),
+    TimeConstant { neuron: u32, value: f64 },
+    InvalidGenome(Vec<Violation>),
+    InnovationOverflow,
+    NoStopCriterion,
+}
+
+impl fmt::Display for NeatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NeatError::Io(error) => write!(f, "{}", error),
+            NeatError::Format(message) => write!(f, "malformed data: {}", message),
+            NeatError::Parse(error) => write!(f, "could not parse genome: {}", error),
+            NeatError::Config(error) => write!(f, "invalid config: {}", error),
+            NeatError::Ini(error) => write!(f, "invalid config file: {}", error),
+            NeatError::DuplicateInnovation(innovation) => write!(f, "duplicate innovation number {}", innovation),
+            NeatError::DuplicateConnection { neuron_in, neuron_out } => {
+                write!(f, "duplicate connection {} -> {}", neuron_in, neuron_out)
+            },
+            NeatError::DanglingNeuron { connection, neuron } => {
+                write!(f, "connection {} references unknown neuron {}", connection, neuron)
+            },
+            NeatError::IncompatibleGenomes { innovation } => {
+                write!(f, "innovation {} connects different neurons in each parent", innovation)
+            },
+            NeatError::FitnessCount { expected, found } => {
+                write!(f, "expected {} fitness values, the evaluator returned {}", expected, found)
+            },
+            NeatError::EmptyPopulation => write!(f, "the population is empty"),
+            NeatError::Cycle { neuron } => {
+                write!(f, "neuron {} is part of a cycle, which a feed-forward network cannot evaluate", neuron)
+            },
+            NeatError::InvalidIdentifier(name) => write!(f, "`{}` is not a valid identifier", name),
+            NeatError::TimeConstant { neuron, value } => {
+                write!(f, "neuron {} has time constant {}, which is not positive", neuron, value)
+            },
+            NeatError::InvalidGenome(violations) => {
+                let violations: Vec<String> = violations.iter().map(Violation::to_string).collect();
+                write!(f, "invalid genome: {}", violations.join("; "))
+            },
+            NeatError::InnovationOverflow => write!(f, "innovation numbers are exhausted"),
+            NeatError::NoStopCriterion => write!(f, "the run has no stop criterion and would never end"),
+        }
+    }
+}
+
+impl Error for NeatError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NeatError::Io(error) => Some(error),
+            NeatError::Parse(error) => Some(error),
+            NeatError::Config(error) => Some(error),
+            NeatError::Ini(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for NeatError {
+    fn from(error: io::Error) -> NeatError {
+        NeatError::Io(error)
+    }
+}
+
+impl From<ParseGenomeError> for NeatError {
+    fn from(error: ParseGenomeError) -> NeatError {
+        NeatError::Parse(error)
+    }
+}
+
+impl From<ConfigError> for NeatError {
+    fn from(error: ConfigError) -> NeatError {
+        NeatError::Config(error)
+    }
+}
+
+impl From<IniError> for NeatError {
+    fn from(error: IniError) -> NeatError {
+        NeatError::Ini(error)
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use crate::genome::Genome;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub trait FitnessEvaluator {
+    fn evaluate(&mut self, genome: &Genome) -> f64;
+
+    fn evaluate_population(&mut self, genomes: &[Genome]) -> Vec<f64> {
+        genomes.iter().map(|genome| self.evaluate(genome)).collect()
+    }
+
+    // Extra data for the genomes of the last `evaluate_population` call, in
+    // population order. Evaluators that do not record any return nothing.
+    fn take_extra_data(&mut self) -> Vec<ExtraData> {
+        Vec::new()
+    }
+}
+
+impl<F> FitnessEvaluator for F
+where
+    F: FnMut(&Genome) -> f64
+{
+    fn evaluate(&mut self, genome: &Genome) -> f64 {
+        self(genome)
+    }
+}
+
+#[cfg(feature = "parallel")]
+pub struct ParallelEvaluator<F>(pub F)
+where
+    F: Fn(&Genome) -> f64 + Sync;
+
+#[cfg(feature = "parallel")]
+impl<F> FitnessEvaluator for ParallelEvaluator<F>
+where
+    F: Fn(&Genome) -> f64 + Sync
+{
+    fn evaluate(&mut self, genome: &Genome) -> f64 {
+        (self.0)(genome)
+    }
+
+    fn evaluate_population(&mut self, genomes: &[Genome]) -> Vec<f64> {
+        genomes.par_iter().map(&self.0).collect()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExtraData {
+    behaviour: Vec<f64>,
+    metrics: HashMap<String, f64>,
+}
+
+impl ExtraData {
+    pub fn new() -> ExtraData {
+        ExtraData::default()
+    }
+
+    pub fn set_behaviour(&mut self, behaviour: Vec<f64>) {
+        self.behaviour = behaviour;
+    }
+
+    pub fn get_behaviour(&self) -> &[f64] {
+        &self.behaviour
+    }
+
+    pub fn set_metric(&mut self, name: &str, value: f64) {
+        self.metrics.insert(name.to_string(), value);
+    }
+
+    pub fn get_metric(&self, name: &str) -> Option<f64> {
+        self.metrics.get(name).copied()
+    }
+
+    pub fn iter_metrics(&self) -> impl Iterator<Item = (&str, f64)> + '_ {
+        self.metrics.iter().map(|(name, &value)| (name.as_str(), value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ GenomeConfig, InnovationCounter };
+
+    struct CountingEvaluator {
+        calls: usize,
+        extra: Vec<ExtraData>,
+    }
+
+    impl FitnessEvaluator for CountingEvaluator {
+        fn evaluate(&mut self, genome: &Genome) -> f64 {
+            self.calls += 1;
+            let mut extra = ExtraData::new();
+            extra.set_metric("connections", genome.iter_connections().count() as f64);
+            self.extra.push(extra);
+            self.calls as f64
+        }
+
+        fn take_extra_data(&mut self) -> Vec<ExtraData> {
+            std::mem::take(&mut self.extra)
+        }
+    }
+
+    #[test]
+    fn test_evaluator_state() {
+        let mut config = GenomeConfig::new(2, 1);
+        config.set_is_connected(true);
+        let mut counter = InnovationCounter::new(3);
+        let genomes: Vec<Genome> = (0..3).map(|_| Genome::new(&mut counter, &config).unwrap()).collect();
+
+        let mut evaluator = CountingEvaluator { calls: 0, extra: Vec::new() };
+
+        assert_eq!(evaluator.evaluate_population(&genomes), vec![1.0, 2.0, 3.0]);
+
+        let extra = evaluator.take_extra_data();
+        assert_eq!(extra.len(), 3);
+        assert_eq!(extra[0].get_metric("connections"), Some(2.0));
+        assert!(evaluator.take_extra_data().is_empty());
+
+        let mut closure = |genome: &Genome| genome.iter_neurons().count() as f64;
+        assert_eq!(closure.evaluate_population(&genomes), vec![3.0; 3]);
+    }
+}
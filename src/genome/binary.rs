@@ -0,0 +1,341 @@
+use std::convert::TryFrom;
+use std::io::{ self, Read, Write };
+use super::{ Genome, Gene };
+use super::connection_gene::ConnectionGene;
+use super::neuron_gene::NeuronGene;
+use crate::NeatError;
+
+const GENOME_MAGIC: &[u8; 4] = b"NEAG";
+const POPULATION_MAGIC: &[u8; 4] = b"NEAP";
+pub const VERSION: u8 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WeightPrecision {
+    F32,
+    F64,
+}
+
+impl WeightPrecision {
+    fn to_flag(self) -> u8 {
+        match self {
+            WeightPrecision::F32 => 0,
+            WeightPrecision::F64 => 1,
+        }
+    }
+
+    fn from_flag(flag: u8) -> Result<WeightPrecision, NeatError> {
+        match flag {
+            0 => Ok(WeightPrecision::F32),
+            1 => Ok(WeightPrecision::F64),
+            flag => Err(invalid_data(format!("unknown weight precision {}", flag)))
+        }
+    }
+}
+
+// Layout (version 2), all integers LEB128 varints:
+//   magic, version byte, precision byte,
+//   neuron count, per neuron: innovation delta, class, bias, time constant,
+//   connection count, per connection: (innovation delta << 1 | enabled), in, out, weight.
+// The precision byte only applies to weights. Bias and time constant are always f64,
+// since rounding a small time constant to f32 could make it zero and unloadable.
+// Version 1 lacks the bias and time constant, which read back as their defaults.
+// Readers dispatch on the version byte so archives stay readable when genes gain fields.
+impl Genome {
+    pub fn write_binary<W: Write>(&self, mut writer: W, precision: WeightPrecision) -> io::Result<()> {
+        writer.write_all(GENOME_MAGIC)?;
+        writer.write_all(&[VERSION, precision.to_flag()])?;
+        write_genome_body(&mut writer, self, precision)
+    }
+
+    pub fn read_binary<R: Read>(mut reader: R) -> Result<Genome, NeatError> {
+        let (version, precision) = read_header(&mut reader, GENOME_MAGIC)?;
+        read_genome_body(&mut reader, version, precision)
+    }
+
+    pub fn to_bytes(&self, precision: WeightPrecision) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write_binary(&mut bytes, precision).expect("writing to a Vec cannot fail");
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Genome, NeatError> {
+        Genome::read_binary(bytes)
+    }
+}
+
+pub fn write_population<W: Write>(mut writer: W, genomes: &[Genome], precision: WeightPrecision) -> io::Result<()> {
+    writer.write_all(POPULATION_MAGIC)?;
+    writer.write_all(&[VERSION, precision.to_flag()])?;
+    write_varint(&mut writer, genomes.len() as u64)?;
+    for genome in genomes {
+        write_genome_body(&mut writer, genome, precision)?;
+    }
+    Ok(())
+}
+
+pub fn read_population<R: Read>(mut reader: R) -> Result<Vec<Genome>, NeatError> {
+    let (version, precision) = read_header(&mut reader, POPULATION_MAGIC)?;
+    let n_genomes = read_varint(&mut reader)?;
+    (0..n_genomes).map(|_| read_genome_body(&mut reader, version, precision)).collect()
+}
+
+fn read_header<R: Read>(reader: &mut R, magic: &[u8; 4]) -> Result<(u8, WeightPrecision), NeatError> {
+    let mut header = [0; 6];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != magic {
+        return Err(invalid_data("bad magic header"));
+    }
+    let version = header[4];
+    if version == 0 || version > VERSION {
+        return Err(invalid_data(format!("unsupported format version {}", version)));
+    }
+    Ok((version, WeightPrecision::from_flag(header[5])?))
+}
+
+fn write_genome_body<W: Write>(writer: &mut W, genome: &Genome, precision: WeightPrecision) -> io::Result<()> {
+    write_varint(writer, genome.neurons.len() as u64)?;
+    let mut previous = 0;
+    for neuron in genome.iter_neurons() {
+        write_varint(writer, (neuron.get_innovation_number() - previous) as u64)?;
+        write_varint(writer, neuron.get_class() as u64)?;
+        write_float(writer, neuron.get_bias(), WeightPrecision::F64)?;
+        write_float(writer, neuron.get_time_constant(), WeightPrecision::F64)?;
+        previous = neuron.get_innovation_number();
+    }
+
+    write_varint(writer, genome.connections.len() as u64)?;
+    let mut previous = 0;
+    for connection in genome.iter_connections() {
+        let delta = (connection.get_innovation_number() - previous) as u64;
+        write_varint(writer, delta << 1 | connection.is_enabled() as u64)?;
+        write_varint(writer, connection.get_neuron_in() as u64)?;
+        write_varint(writer, connection.get_neuron_out() as u64)?;
+        write_float(writer, connection.get_weight(), precision)?;
+        previous = connection.get_innovation_number();
+    }
+    Ok(())
+}
+
+fn read_genome_body<R: Read>(reader: &mut R, version: u8, precision: WeightPrecision) -> Result<Genome, NeatError> {
+    let has_parameters = match version {
+        1 => false,
+        2 => true,
+        version => return Err(invalid_data(format!("unsupported format version {}", version)))
+    };
+
+    let n_neurons = read_varint(reader)?;
+    let mut neurons = Vec::new();
+    let mut innovation = 0;
+    for _ in 0..n_neurons {
+        innovation = checked_add(innovation, read_u32(reader)?)?;
+        let mut neuron = NeuronGene::new(innovation, read_u32(reader)?);
+        if has_parameters {
+            neuron.set_bias(read_float(reader, WeightPrecision::F64)?);
+            neuron.set_time_constant(read_float(reader, WeightPrecision::F64)?);
+        }
+        neurons.push(neuron);
+    }
+
+    let n_connections = read_varint(reader)?;
+    let mut connections = Vec::new();
+    let mut innovation = 0;
+    for _ in 0..n_connections {
+        let packed = read_varint(reader)?;
+        let delta = u32::try_from(packed >> 1).map_err(|_| invalid_data("innovation number out of range"))?;
+        innovation = checked_add(innovation, delta)?;
+        let neuron_in = read_u32(reader)?;
+        let neuron_out = read_u32(reader)?;
+        let weight = read_float(reader, precision)?;
+        let mut connection = ConnectionGene::new(innovation, neuron_in, neuron_out, weight);
+        connection.set_enabled(packed & 1 == 1);
+        connections.push(connection);
+    }
+
+    Genome::from_genes(neurons, connections)
+}
+
+fn write_float<W: Write>(writer: &mut W, value: f64, precision: WeightPrecision) -> io::Result<()> {
+    match precision {
+        WeightPrecision::F32 => writer.write_all(&(value as f32).to_le_bytes()),
+        WeightPrecision::F64 => writer.write_all(&value.to_le_bytes()),
+    }
+}
+
+fn read_float<R: Read>(reader: &mut R, precision: WeightPrecision) -> Result<f64, NeatError> {
+    match precision {
+        WeightPrecision::F32 => {
+            let mut bytes = [0; 4];
+            reader.read_exact(&mut bytes)?;
+            Ok(f32::from_le_bytes(bytes) as f64)
+        },
+        WeightPrecision::F64 => {
+            let mut bytes = [0; 8];
+            reader.read_exact(&mut bytes)?;
+            Ok(f64::from_le_bytes(bytes))
+        }
+    }
+}
+
+fn checked_add(innovation: u32, delta: u32) -> Result<u32, NeatError> {
+    innovation.checked_add(delta).ok_or_else(|| invalid_data("innovation number out of range"))
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> Result<u64, NeatError> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0; 1];
+        reader.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data("varint is too long"))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, NeatError> {
+    u32::try_from(read_varint(reader)?).map_err(|_| invalid_data("value out of range"))
+}
+
+fn invalid_data<E: ToString>(message: E) -> NeatError {
+    NeatError::Format(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ GenomeConfig, InnovationCounter };
+
+    fn sample_genomes() -> Vec<Genome> {
+        let mut config = GenomeConfig::new(3, 2);
+        config.set_is_connected(true);
+        config.set_mutate_create_neuron(0.5);
+        config.set_mutate_bias(0.5);
+        config.set_mutate_time_constant(0.5);
+        let mut counter = InnovationCounter::new(5);
+        (0..5).map(|_| {
+            let mut genome = Genome::new(&mut counter, &config).unwrap();
+            for _ in 0..5 {
+                genome.mutate(&mut counter, &config).unwrap();
+            }
+            genome
+        }).collect()
+    }
+
+    fn assert_same(a: &Genome, b: &Genome, tolerance: f64) {
+        assert_eq!(a.iter_neurons().count(), b.iter_neurons().count());
+        for (x, y) in a.iter_neurons().zip(b.iter_neurons()) {
+            assert_eq!(x.get_innovation_number(), y.get_innovation_number());
+            assert_eq!(x.get_class(), y.get_class());
+            assert!((x.get_bias() - y.get_bias()).abs() <= tolerance);
+            assert!((x.get_time_constant() - y.get_time_constant()).abs() <= tolerance);
+        }
+        assert_eq!(a.iter_connections().count(), b.iter_connections().count());
+        for (x, y) in a.iter_connections().zip(b.iter_connections()) {
+            assert_eq!(x.get_innovation_number(), y.get_innovation_number());
+            assert_eq!((x.get_neuron_in(), x.get_neuron_out()), (y.get_neuron_in(), y.get_neuron_out()));
+            assert_eq!(x.is_enabled(), y.is_enabled());
+            assert!((x.get_weight() - y.get_weight()).abs() <= tolerance);
+        }
+    }
+
+    #[test]
+    fn test_genome_round_trip() {
+        for genome in sample_genomes() {
+            let restored = Genome::from_bytes(&genome.to_bytes(WeightPrecision::F64)).unwrap();
+            assert_same(&genome, &restored, 0.0);
+
+            let bytes = genome.to_bytes(WeightPrecision::F32);
+            assert!(bytes.len() < genome.to_bytes(WeightPrecision::F64).len() || genome.iter_connections().count() == 0);
+            assert_same(&genome, &Genome::from_bytes(&bytes).unwrap(), 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_population_round_trip() {
+        let genomes = sample_genomes();
+        let mut bytes = Vec::new();
+        write_population(&mut bytes, &genomes, WeightPrecision::F64).unwrap();
+
+        let restored = read_population(bytes.as_slice()).unwrap();
+        assert_eq!(restored.len(), genomes.len());
+        for (genome, restored) in genomes.iter().zip(restored.iter()) {
+            assert_same(genome, restored, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_version_1_is_readable() {
+        let mut connection = ConnectionGene::new(3, 0, 1, 0.5);
+        connection.set_enabled(false);
+        let genome = Genome::from_genes(vec![NeuronGene::new(0, 1), NeuronGene::new(1, 2)], vec![connection]).unwrap();
+
+        let mut version_1 = b"NEAG".to_vec();
+        version_1.extend_from_slice(&[1, 0, 2, 0, 1, 1, 2, 1, 6, 0, 1]);
+        version_1.extend_from_slice(&0.5f32.to_le_bytes());
+        assert_same(&genome, &Genome::from_bytes(&version_1).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_version_2_layout_is_stable() {
+        let mut connection = ConnectionGene::new(3, 0, 1, 0.5);
+        connection.set_enabled(false);
+        let mut output = NeuronGene::new(1, 2);
+        output.set_bias(-1.0);
+        output.set_time_constant(0.25);
+        let genome = Genome::from_genes(vec![NeuronGene::new(0, 1), output], vec![connection]).unwrap();
+
+        let mut expected = b"NEAG".to_vec();
+        expected.extend_from_slice(&[2, 0, 2, 0, 1]);
+        expected.extend_from_slice(&0.0f64.to_le_bytes());
+        expected.extend_from_slice(&1.0f64.to_le_bytes());
+        expected.extend_from_slice(&[1, 2]);
+        expected.extend_from_slice(&(-1.0f64).to_le_bytes());
+        expected.extend_from_slice(&0.25f64.to_le_bytes());
+        expected.extend_from_slice(&[1, 6, 0, 1]);
+        expected.extend_from_slice(&0.5f32.to_le_bytes());
+        assert_eq!(genome.to_bytes(WeightPrecision::F32), expected);
+
+        assert_same(&genome, &Genome::from_bytes(&expected).unwrap(), 0.0);
+
+        // Below the smallest f32 this would round to zero, which no genome accepts.
+        let mut output = NeuronGene::new(1, 2);
+        output.set_time_constant(1e-50);
+        let genome = Genome::from_genes(vec![NeuronGene::new(0, 1), output], vec![]).unwrap();
+        let restored = Genome::from_bytes(&genome.to_bytes(WeightPrecision::F32)).unwrap();
+        assert_eq!(restored.iter_neurons().nth(1).unwrap().get_time_constant(), 1e-50);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let bytes = sample_genomes()[0].to_bytes(WeightPrecision::F64);
+
+        assert!(matches!(Genome::from_bytes(b"NOPE\x01\x01"), Err(NeatError::Format(_))));
+
+        let mut future = bytes.clone();
+        future[4] = VERSION + 1;
+        assert!(Genome::from_bytes(&future).unwrap_err().to_string().contains("version"));
+
+        match Genome::from_bytes(&bytes[..bytes.len() - 1]) {
+            Err(NeatError::Io(error)) => assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof),
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let mut dangling = Vec::new();
+        dangling.extend_from_slice(b"NEAG\x01\x00");
+        dangling.extend_from_slice(&[1, 0, 1, 1, 2, 0, 5]);
+        dangling.extend_from_slice(&1.0f32.to_le_bytes());
+        assert!(matches!(Genome::from_bytes(&dangling), Err(NeatError::DanglingNeuron { connection: 1, neuron: 5 })));
+    }
+}
@@ -1,7 +1,11 @@
 #![allow(dead_code)]
 
 use super::Gene;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
 
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ConnectionGene {
     innovation_number: u32,
     neuron_in: u32,
@@ -41,6 +45,10 @@ impl ConnectionGene {
         self.weight = weight;
     }
 
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
     pub fn toggle_enabled(&mut self) {
         self.enabled = !self.enabled;
     }
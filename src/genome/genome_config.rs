@@ -1,19 +1,47 @@
 extern crate rand;
 
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::io;
+#[cfg(any(feature = "toml-config", feature = "json-config"))]
+use std::{ fs, path::Path };
 use rand::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
 
+// Deserializing goes through GenomeConfigBuilder, so missing options take their
+// defaults and the result is validated like any other built config.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(try_from = "GenomeConfigBuilder"))]
 pub struct GenomeConfig {
     n_sensor: u32,
     n_output: u32,
     is_connected: bool,
+    feed_forward: bool,
     default_weight: f64,
     weight_is_random: bool,
     weight_deviation: f64,
+    weight_min_value: f64,
+    weight_max_value: f64,
     mutate_create_connection: f64,
     mutate_create_neuron: f64,
     mutate_set_weight: f64,
     mutate_update_weight: f64,
     mutate_toggle_connection: f64,
+    compatibility_disjoint_coefficient: f64,
+    compatibility_weight_coefficient: f64,
+    // Neuron gene mutations. Only CtrnnNetwork reads the bias and time constant,
+    // so both stay off unless a continuous-time network is evolved.
+    mutate_bias: f64,
+    bias_deviation: f64,
+    bias_min_value: f64,
+    bias_max_value: f64,
+    mutate_time_constant: f64,
+    time_constant_deviation: f64,
+    time_constant_min_value: f64,
+    time_constant_max_value: f64,
 }
 
 impl GenomeConfig {
@@ -22,14 +50,27 @@ impl GenomeConfig {
             n_sensor,
             n_output,
             is_connected: false,
+            feed_forward: default_feed_forward(),
             default_weight: 0.0,
             weight_is_random: true,
             weight_deviation: 3.0,
+            weight_min_value: default_weight_min_value(),
+            weight_max_value: default_weight_max_value(),
             mutate_create_connection: 0.05,
             mutate_create_neuron: 0.03,
             mutate_set_weight: 0.15,
             mutate_update_weight: 0.2,
             mutate_toggle_connection: 0.1,
+            compatibility_disjoint_coefficient: 1.0,
+            compatibility_weight_coefficient: 0.5,
+            mutate_bias: 0.0,
+            bias_deviation: default_bias_deviation(),
+            bias_min_value: default_bias_min_value(),
+            bias_max_value: default_bias_max_value(),
+            mutate_time_constant: 0.0,
+            time_constant_deviation: default_time_constant_deviation(),
+            time_constant_min_value: default_time_constant_min_value(),
+            time_constant_max_value: default_time_constant_max_value(),
         }
     }
 
@@ -66,17 +107,37 @@ impl GenomeConfig {
     }
 
     pub fn get_mutate_create_neuron(&self) -> f64 {
-        self.mutate_create_connection
+        self.mutate_create_neuron
     }
 
     pub fn get_mutate_create_connection(&self) -> f64 {
         self.mutate_create_connection
     }
 
+    pub fn set_compatibility_disjoint_coefficient(&mut self, value: f64) {
+        self.compatibility_disjoint_coefficient = value;
+    }
+
+    pub fn set_compatibility_weight_coefficient(&mut self, value: f64) {
+        self.compatibility_weight_coefficient = value;
+    }
+
+    pub fn get_compatibility_disjoint_coefficient(&self) -> f64 {
+        self.compatibility_disjoint_coefficient
+    }
+
+    pub fn get_compatibility_weight_coefficient(&self) -> f64 {
+        self.compatibility_weight_coefficient
+    }
+
     pub fn set_is_connected(&mut self, is_connected: bool) {
         self.is_connected = is_connected;
     }
 
+    pub fn set_feed_forward(&mut self, feed_forward: bool) {
+        self.feed_forward = feed_forward;
+    }
+
     pub fn set_default_weight(&mut self, default_weight: f64) {
         self.default_weight = default_weight;
     }
@@ -89,6 +150,14 @@ impl GenomeConfig {
         self.weight_deviation = weight_deviation;
     }
 
+    pub fn set_weight_min_value(&mut self, weight_min_value: f64) {
+        self.weight_min_value = weight_min_value;
+    }
+
+    pub fn set_weight_max_value(&mut self, weight_max_value: f64) {
+        self.weight_max_value = weight_max_value;
+    }
+
     pub fn get_n_sensor(&self) -> u32 {
         self.n_sensor
     }
@@ -101,6 +170,10 @@ impl GenomeConfig {
         self.is_connected
     }
 
+    pub fn is_feed_forward(&self) -> bool {
+        self.feed_forward
+    }
+
     pub fn is_weight_random(&self) -> bool {
         self.weight_is_random
     }
@@ -109,15 +182,635 @@ impl GenomeConfig {
         self.weight_deviation
     }
 
+    pub fn get_default_weight(&self) -> f64 {
+        self.default_weight
+    }
+
+    pub fn get_weight_min_value(&self) -> f64 {
+        self.weight_min_value
+    }
+
+    pub fn get_weight_max_value(&self) -> f64 {
+        self.weight_max_value
+    }
+
+    pub fn set_mutate_bias(&mut self, value: f64) {
+        self.mutate_bias = value;
+    }
+
+    pub fn set_bias_deviation(&mut self, bias_deviation: f64) {
+        self.bias_deviation = bias_deviation;
+    }
+
+    pub fn set_bias_min_value(&mut self, bias_min_value: f64) {
+        self.bias_min_value = bias_min_value;
+    }
+
+    pub fn set_bias_max_value(&mut self, bias_max_value: f64) {
+        self.bias_max_value = bias_max_value;
+    }
+
+    pub fn set_mutate_time_constant(&mut self, value: f64) {
+        self.mutate_time_constant = value;
+    }
+
+    pub fn set_time_constant_deviation(&mut self, time_constant_deviation: f64) {
+        self.time_constant_deviation = time_constant_deviation;
+    }
+
+    pub fn set_time_constant_min_value(&mut self, time_constant_min_value: f64) {
+        self.time_constant_min_value = time_constant_min_value;
+    }
+
+    pub fn set_time_constant_max_value(&mut self, time_constant_max_value: f64) {
+        self.time_constant_max_value = time_constant_max_value;
+    }
+
+    pub fn get_mutate_bias(&self) -> f64 {
+        self.mutate_bias
+    }
+
+    pub fn get_bias_deviation(&self) -> f64 {
+        self.bias_deviation
+    }
+
+    pub fn get_bias_min_value(&self) -> f64 {
+        self.bias_min_value
+    }
+
+    pub fn get_bias_max_value(&self) -> f64 {
+        self.bias_max_value
+    }
+
+    pub fn get_mutate_time_constant(&self) -> f64 {
+        self.mutate_time_constant
+    }
+
+    pub fn get_time_constant_deviation(&self) -> f64 {
+        self.time_constant_deviation
+    }
+
+    pub fn get_time_constant_min_value(&self) -> f64 {
+        self.time_constant_min_value
+    }
+
+    pub fn get_time_constant_max_value(&self) -> f64 {
+        self.time_constant_max_value
+    }
+
+    pub fn clamp_bias(&self, bias: f64) -> f64 {
+        bias.max(self.bias_min_value).min(self.bias_max_value)
+    }
+
+    pub fn clamp_time_constant(&self, time_constant: f64) -> f64 {
+        time_constant.max(self.time_constant_min_value).min(self.time_constant_max_value)
+    }
+
+    pub fn clamp_weight(&self, weight: f64) -> f64 {
+        weight.max(self.weight_min_value).min(self.weight_max_value)
+    }
+
     pub fn get_random_weight(&self) -> f64 {
-        self.default_weight + thread_rng().gen::<f64>() * 2.0 * self.weight_deviation - self.weight_deviation
+        self.get_random_weight_with(&mut thread_rng())
+    }
+
+    pub fn get_random_weight_with<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.clamp_weight(self.default_weight + rng.gen::<f64>() * 2.0 * self.weight_deviation - self.weight_deviation)
     }
 
     pub fn get_weight(&self) -> f64 {
+        self.get_weight_with(&mut thread_rng())
+    }
+
+    pub fn get_weight_with<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
         if self.weight_is_random {
-            self.get_random_weight()
+            self.get_random_weight_with(rng)
         } else {
             self.default_weight
         }
     }
+
+    pub fn builder(n_sensor: u32, n_output: u32) -> GenomeConfigBuilder {
+        GenomeConfigBuilder::new(n_sensor, n_output)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.n_sensor == 0 {
+            return Err(ConfigError::NoSensors);
+        }
+        if self.n_output == 0 {
+            return Err(ConfigError::NoOutputs);
+        }
+
+        let probabilities = [
+            ("mutate_create_connection", self.mutate_create_connection),
+            ("mutate_create_neuron", self.mutate_create_neuron),
+            ("mutate_set_weight", self.mutate_set_weight),
+            ("mutate_update_weight", self.mutate_update_weight),
+            ("mutate_toggle_connection", self.mutate_toggle_connection),
+            ("mutate_bias", self.mutate_bias),
+            ("mutate_time_constant", self.mutate_time_constant),
+        ];
+        for &(name, value) in probabilities.iter() {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(ConfigError::Probability { name, value });
+            }
+        }
+
+        let non_negative = [
+            ("weight_deviation", self.weight_deviation),
+            ("compatibility_disjoint_coefficient", self.compatibility_disjoint_coefficient),
+            ("compatibility_weight_coefficient", self.compatibility_weight_coefficient),
+            ("bias_deviation", self.bias_deviation),
+            ("time_constant_deviation", self.time_constant_deviation),
+        ];
+        for &(name, value) in non_negative.iter() {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(ConfigError::OutOfRange { name, value, expected: "a finite value >= 0" });
+            }
+        }
+
+        // Infinite bounds are allowed and simply disable clamping.
+        let (min, max) = (self.weight_min_value, self.weight_max_value);
+        if min.is_nan() || max.is_nan() || min > max {
+            return Err(ConfigError::WeightBounds { min, max });
+        }
+        if !(self.default_weight >= min && self.default_weight <= max && self.default_weight.is_finite()) {
+            return Err(ConfigError::DefaultWeight { value: self.default_weight, min, max });
+        }
+
+        let (min, max) = (self.bias_min_value, self.bias_max_value);
+        if min.is_nan() || max.is_nan() || min > max {
+            return Err(ConfigError::BiasBounds { min, max });
+        }
+
+        // Time constants divide the derivative of a continuous-time neuron, so they must stay positive.
+        let (min, max) = (self.time_constant_min_value, self.time_constant_max_value);
+        if !(min > 0.0 && min <= max) {
+            return Err(ConfigError::TimeConstantBounds { min, max });
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "toml-config")]
+    pub fn from_toml_str(s: &str) -> Result<GenomeConfig, ConfigError> {
+        toml::from_str::<GenomeConfigBuilder>(s)
+            .map_err(|error| ConfigError::Parse(error.to_string()))?
+            .build()
+    }
+
+    #[cfg(feature = "toml-config")]
+    pub fn load_toml<P: AsRef<Path>>(path: P) -> Result<GenomeConfig, ConfigError> {
+        GenomeConfig::from_toml_str(&fs::read_to_string(path)?)
+    }
+
+    #[cfg(feature = "json-config")]
+    pub fn from_json_str(s: &str) -> Result<GenomeConfig, ConfigError> {
+        serde_json::from_str::<GenomeConfigBuilder>(s)
+            .map_err(|error| ConfigError::Parse(error.to_string()))?
+            .build()
+    }
+
+    #[cfg(feature = "json-config")]
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<GenomeConfig, ConfigError> {
+        GenomeConfig::from_json_str(&fs::read_to_string(path)?)
+    }
+}
+
+fn default_feed_forward() -> bool {
+    true
+}
+
+fn default_weight_min_value() -> f64 {
+    -30.0
+}
+
+fn default_weight_max_value() -> f64 {
+    30.0
+}
+
+fn default_bias_deviation() -> f64 {
+    0.5
+}
+
+fn default_bias_min_value() -> f64 {
+    -30.0
+}
+
+fn default_bias_max_value() -> f64 {
+    30.0
+}
+
+fn default_time_constant_deviation() -> f64 {
+    0.1
+}
+
+fn default_time_constant_min_value() -> f64 {
+    0.01
+}
+
+fn default_time_constant_max_value() -> f64 {
+    10.0
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    NoSensors,
+    NoOutputs,
+    Probability { name: &'static str, value: f64 },
+    OutOfRange { name: &'static str, value: f64, expected: &'static str },
+    WeightBounds { min: f64, max: f64 },
+    BiasBounds { min: f64, max: f64 },
+    DefaultWeight { value: f64, min: f64, max: f64 },
+    TimeConstantBounds { min: f64, max: f64 },
+    Parse(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::NoSensors => write!(f, "a genome needs at least one sensor"),
+            ConfigError::NoOutputs => write!(f, "a genome needs at least one output"),
+            ConfigError::Probability { name, value } => {
+                write!(f, "{} must be a probability between 0 and 1, got {}", name, value)
+            },
+            ConfigError::OutOfRange { name, value, expected } => write!(f, "{} must be {}, got {}", name, expected, value),
+            ConfigError::WeightBounds { min, max } => {
+                write!(f, "weight_min_value ({}) must not be greater than weight_max_value ({})", min, max)
+            },
+            ConfigError::BiasBounds { min, max } => {
+                write!(f, "bias_min_value ({}) must not be greater than bias_max_value ({})", min, max)
+            },
+            ConfigError::DefaultWeight { value, min, max } => {
+                write!(f, "default_weight {} is outside the weight bounds [{}, {}]", value, min, max)
+            },
+            ConfigError::TimeConstantBounds { min, max } => {
+                write!(f, "time constant bounds [{}, {}] must be positive and ordered", min, max)
+            },
+            ConfigError::Parse(message) => write!(f, "could not parse config: {}", message),
+            ConfigError::Io(error) => write!(f, "could not read config: {}", error),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> ConfigError {
+        ConfigError::Io(error)
+    }
+}
+
+// Unset options keep the defaults of GenomeConfig::new. When reading TOML or JSON
+// only n_sensor and n_output are required and unknown keys are rejected.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize), serde(deny_unknown_fields))]
+pub struct GenomeConfigBuilder {
+    n_sensor: u32,
+    n_output: u32,
+    is_connected: Option<bool>,
+    feed_forward: Option<bool>,
+    default_weight: Option<f64>,
+    weight_is_random: Option<bool>,
+    weight_deviation: Option<f64>,
+    weight_min_value: Option<f64>,
+    weight_max_value: Option<f64>,
+    mutate_create_connection: Option<f64>,
+    mutate_create_neuron: Option<f64>,
+    mutate_set_weight: Option<f64>,
+    mutate_update_weight: Option<f64>,
+    mutate_toggle_connection: Option<f64>,
+    compatibility_disjoint_coefficient: Option<f64>,
+    compatibility_weight_coefficient: Option<f64>,
+    mutate_bias: Option<f64>,
+    bias_deviation: Option<f64>,
+    bias_min_value: Option<f64>,
+    bias_max_value: Option<f64>,
+    mutate_time_constant: Option<f64>,
+    time_constant_deviation: Option<f64>,
+    time_constant_min_value: Option<f64>,
+    time_constant_max_value: Option<f64>,
+}
+
+impl GenomeConfigBuilder {
+    pub fn new(n_sensor: u32, n_output: u32) -> GenomeConfigBuilder {
+        GenomeConfigBuilder {
+            n_sensor,
+            n_output,
+            is_connected: None,
+            feed_forward: None,
+            default_weight: None,
+            weight_is_random: None,
+            weight_deviation: None,
+            weight_min_value: None,
+            weight_max_value: None,
+            mutate_create_connection: None,
+            mutate_create_neuron: None,
+            mutate_set_weight: None,
+            mutate_update_weight: None,
+            mutate_toggle_connection: None,
+            compatibility_disjoint_coefficient: None,
+            compatibility_weight_coefficient: None,
+            mutate_bias: None,
+            bias_deviation: None,
+            bias_min_value: None,
+            bias_max_value: None,
+            mutate_time_constant: None,
+            time_constant_deviation: None,
+            time_constant_min_value: None,
+            time_constant_max_value: None,
+        }
+    }
+
+    pub fn is_connected(mut self, is_connected: bool) -> GenomeConfigBuilder {
+        self.is_connected = Some(is_connected);
+        self
+    }
+
+    pub fn feed_forward(mut self, feed_forward: bool) -> GenomeConfigBuilder {
+        self.feed_forward = Some(feed_forward);
+        self
+    }
+
+    pub fn default_weight(mut self, default_weight: f64) -> GenomeConfigBuilder {
+        self.default_weight = Some(default_weight);
+        self
+    }
+
+    pub fn weight_is_random(mut self, weight_is_random: bool) -> GenomeConfigBuilder {
+        self.weight_is_random = Some(weight_is_random);
+        self
+    }
+
+    pub fn weight_deviation(mut self, weight_deviation: f64) -> GenomeConfigBuilder {
+        self.weight_deviation = Some(weight_deviation);
+        self
+    }
+
+    pub fn weight_bounds(mut self, min: f64, max: f64) -> GenomeConfigBuilder {
+        self.weight_min_value = Some(min);
+        self.weight_max_value = Some(max);
+        self
+    }
+
+    pub fn mutate_create_connection(mut self, value: f64) -> GenomeConfigBuilder {
+        self.mutate_create_connection = Some(value);
+        self
+    }
+
+    pub fn mutate_create_neuron(mut self, value: f64) -> GenomeConfigBuilder {
+        self.mutate_create_neuron = Some(value);
+        self
+    }
+
+    pub fn mutate_set_weight(mut self, value: f64) -> GenomeConfigBuilder {
+        self.mutate_set_weight = Some(value);
+        self
+    }
+
+    pub fn mutate_update_weight(mut self, value: f64) -> GenomeConfigBuilder {
+        self.mutate_update_weight = Some(value);
+        self
+    }
+
+    pub fn mutate_toggle_connection(mut self, value: f64) -> GenomeConfigBuilder {
+        self.mutate_toggle_connection = Some(value);
+        self
+    }
+
+    pub fn compatibility_disjoint_coefficient(mut self, value: f64) -> GenomeConfigBuilder {
+        self.compatibility_disjoint_coefficient = Some(value);
+        self
+    }
+
+    pub fn compatibility_weight_coefficient(mut self, value: f64) -> GenomeConfigBuilder {
+        self.compatibility_weight_coefficient = Some(value);
+        self
+    }
+
+    pub fn mutate_bias(mut self, value: f64) -> GenomeConfigBuilder {
+        self.mutate_bias = Some(value);
+        self
+    }
+
+    pub fn bias_deviation(mut self, bias_deviation: f64) -> GenomeConfigBuilder {
+        self.bias_deviation = Some(bias_deviation);
+        self
+    }
+
+    pub fn bias_bounds(mut self, min: f64, max: f64) -> GenomeConfigBuilder {
+        self.bias_min_value = Some(min);
+        self.bias_max_value = Some(max);
+        self
+    }
+
+    pub fn mutate_time_constant(mut self, value: f64) -> GenomeConfigBuilder {
+        self.mutate_time_constant = Some(value);
+        self
+    }
+
+    pub fn time_constant_deviation(mut self, time_constant_deviation: f64) -> GenomeConfigBuilder {
+        self.time_constant_deviation = Some(time_constant_deviation);
+        self
+    }
+
+    pub fn time_constant_bounds(mut self, min: f64, max: f64) -> GenomeConfigBuilder {
+        self.time_constant_min_value = Some(min);
+        self.time_constant_max_value = Some(max);
+        self
+    }
+
+    pub fn build(&self) -> Result<GenomeConfig, ConfigError> {
+        let mut config = GenomeConfig::new(self.n_sensor, self.n_output);
+        if let Some(value) = self.is_connected {
+            config.set_is_connected(value);
+        }
+        if let Some(value) = self.feed_forward {
+            config.set_feed_forward(value);
+        }
+        if let Some(value) = self.default_weight {
+            config.set_default_weight(value);
+        }
+        if let Some(value) = self.weight_is_random {
+            config.set_weight_is_random(value);
+        }
+        if let Some(value) = self.weight_deviation {
+            config.set_weight_deviation(value);
+        }
+        if let Some(value) = self.weight_min_value {
+            config.set_weight_min_value(value);
+        }
+        if let Some(value) = self.weight_max_value {
+            config.set_weight_max_value(value);
+        }
+        if let Some(value) = self.mutate_create_connection {
+            config.set_mutate_create_connection(value);
+        }
+        if let Some(value) = self.mutate_create_neuron {
+            config.set_mutate_create_neuron(value);
+        }
+        if let Some(value) = self.mutate_set_weight {
+            config.set_mutate_set_weight(value);
+        }
+        if let Some(value) = self.mutate_update_weight {
+            config.set_mutate_update_weight(value);
+        }
+        if let Some(value) = self.mutate_toggle_connection {
+            config.set_mutate_toggle_connection(value);
+        }
+        if let Some(value) = self.compatibility_disjoint_coefficient {
+            config.set_compatibility_disjoint_coefficient(value);
+        }
+        if let Some(value) = self.compatibility_weight_coefficient {
+            config.set_compatibility_weight_coefficient(value);
+        }
+        if let Some(value) = self.mutate_bias {
+            config.set_mutate_bias(value);
+        }
+        if let Some(value) = self.bias_deviation {
+            config.set_bias_deviation(value);
+        }
+        if let Some(value) = self.bias_min_value {
+            config.set_bias_min_value(value);
+        }
+        if let Some(value) = self.bias_max_value {
+            config.set_bias_max_value(value);
+        }
+        if let Some(value) = self.mutate_time_constant {
+            config.set_mutate_time_constant(value);
+        }
+        if let Some(value) = self.time_constant_deviation {
+            config.set_time_constant_deviation(value);
+        }
+        if let Some(value) = self.time_constant_min_value {
+            config.set_time_constant_min_value(value);
+        }
+        if let Some(value) = self.time_constant_max_value {
+            config.set_time_constant_max_value(value);
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GenomeConfigBuilder> for GenomeConfig {
+    type Error = ConfigError;
+
+    fn try_from(builder: GenomeConfigBuilder) -> Result<GenomeConfig, ConfigError> {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let config = GenomeConfig::builder(3, 2)
+            .is_connected(true)
+            .weight_bounds(-1.0, 1.0)
+            .weight_deviation(5.0)
+            .mutate_create_neuron(0.5)
+            .build()
+            .unwrap();
+
+        assert!(config.is_connected());
+        assert_eq!(config.get_mutate_create_neuron(), 0.5);
+        assert_eq!(config.get_mutate_create_connection(), 0.05);
+        for _ in 0..100 {
+            let weight = config.get_random_weight();
+            assert!((-1.0..=1.0).contains(&weight));
+        }
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        assert!(matches!(GenomeConfig::builder(0, 1).build(), Err(ConfigError::NoSensors)));
+        assert!(matches!(GenomeConfig::builder(1, 0).build(), Err(ConfigError::NoOutputs)));
+        assert!(matches!(
+            GenomeConfig::builder(1, 1).mutate_update_weight(-0.1).build(),
+            Err(ConfigError::Probability { name: "mutate_update_weight", .. })
+        ));
+        assert!(matches!(
+            GenomeConfig::builder(1, 1).mutate_toggle_connection(f64::NAN).build(),
+            Err(ConfigError::Probability { .. })
+        ));
+        assert!(matches!(
+            GenomeConfig::builder(1, 1).weight_deviation(-3.0).build(),
+            Err(ConfigError::OutOfRange { name: "weight_deviation", .. })
+        ));
+        assert!(matches!(
+            GenomeConfig::builder(1, 1).weight_bounds(2.0, 1.0).build(),
+            Err(ConfigError::WeightBounds { .. })
+        ));
+        assert!(matches!(
+            GenomeConfig::builder(1, 1).default_weight(50.0).build(),
+            Err(ConfigError::DefaultWeight { .. })
+        ));
+        assert!(matches!(
+            GenomeConfig::builder(1, 1).bias_bounds(1.0, -1.0).build(),
+            Err(ConfigError::BiasBounds { .. })
+        ));
+        assert!(matches!(
+            GenomeConfig::builder(1, 1).time_constant_bounds(0.0, 1.0).build(),
+            Err(ConfigError::TimeConstantBounds { .. })
+        ));
+        assert!(matches!(
+            GenomeConfig::builder(1, 1).mutate_time_constant(2.0).build(),
+            Err(ConfigError::Probability { name: "mutate_time_constant", .. })
+        ));
+
+        let error = GenomeConfig::builder(1, 1).mutate_create_neuron(1.5).build().unwrap_err();
+        assert_eq!(error.to_string(), "mutate_create_neuron must be a probability between 0 and 1, got 1.5");
+    }
+
+    #[cfg(feature = "toml-config")]
+    #[test]
+    fn test_from_toml() {
+        let config = GenomeConfig::from_toml_str("
+            n_sensor = 2
+            n_output = 1
+            is_connected = true
+            weight_min_value = -5.0
+            weight_max_value = 5.0
+        ").unwrap();
+        assert_eq!(config.get_n_sensor(), 2);
+        assert_eq!(config.get_weight_max_value(), 5.0);
+
+        assert!(matches!(GenomeConfig::from_toml_str("n_sensor = 2\nn_output = 1\nconnected = true"), Err(ConfigError::Parse(_))));
+        assert!(matches!(
+            GenomeConfig::from_toml_str("n_sensor = 2\nn_output = 1\nmutate_set_weight = 2.0"),
+            Err(ConfigError::Probability { .. })
+        ));
+    }
+
+    #[cfg(feature = "json-config")]
+    #[test]
+    fn test_from_json() {
+        let config = GenomeConfig::from_json_str(r#"{ "n_sensor": 4, "n_output": 2, "mutate_create_neuron": 0.1 }"#).unwrap();
+        assert_eq!(config.get_n_output(), 2);
+        assert_eq!(config.get_mutate_create_neuron(), 0.1);
+
+        assert!(matches!(GenomeConfig::from_json_str(r#"{ "n_sensor": 4 }"#), Err(ConfigError::Parse(_))));
+        assert!(matches!(GenomeConfig::from_json_str(r#"{ "n_sensor": 0, "n_output": 1 }"#), Err(ConfigError::NoSensors)));
+
+        let json = serde_json::to_string(&config).unwrap();
+        let restored: GenomeConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+        let invalid = json.replace(r#""mutate_create_neuron":0.1"#, r#""mutate_create_neuron":1.5"#);
+        assert_ne!(invalid, json);
+        let error = serde_json::from_str::<GenomeConfig>(&invalid).unwrap_err();
+        assert!(error.to_string().contains("mutate_create_neuron"));
+    }
 }
@@ -1,9 +1,13 @@
 extern crate rand;
 
-mod connection_gene;
-mod neuron_gene;
+pub mod connection_gene;
+pub mod neuron_gene;
 pub mod genome_config;
+pub mod binary;
+pub mod text;
+pub mod validate;
 
+use std::collections::HashSet;
 use std::slice::Iter;
 use genome_config::GenomeConfig;
 use std::ops::{ Deref, DerefMut };
@@ -12,34 +16,67 @@ use connection_gene::ConnectionGene;
 use neuron_gene::NeuronGene;
 use crate::utils::HashVec;
 use super::InnovationCounter;
-use std::cell::RefCell;
-use std::rc::Rc;
-use rand::{ thread_rng, Rng, seq::index::sample, rngs::ThreadRng };
-
+use crate::NeatError;
+use rand::{ thread_rng, Rng, seq::index::sample, seq::SliceRandom };
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "GenomeData", into = "GenomeData"))]
 pub struct Genome {
-    counter: Rc<RefCell<InnovationCounter>>,
     connections: HashVec<u32, ComparableGeneInterface<ConnectionGene>>,
     neurons: HashVec<u32, ComparableGeneInterface<NeuronGene>>,
-    config: Rc<RefCell<GenomeConfig>>
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct GenomeData {
+    neurons: Vec<NeuronGene>,
+    connections: Vec<ConnectionGene>,
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<GenomeData> for Genome {
+    type Error = NeatError;
+
+    fn try_from(data: GenomeData) -> Result<Genome, NeatError> {
+        Genome::from_genes(data.neurons, data.connections)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Genome> for GenomeData {
+    fn from(genome: Genome) -> GenomeData {
+        GenomeData {
+            neurons: genome.neurons.into_iter().map(|(_, neuron)| neuron.0).collect(),
+            connections: genome.connections.into_iter().map(|(_, connection)| connection.0).collect(),
+        }
+    }
 }
 
 impl Genome {
-    pub fn new(counter: Rc<RefCell<InnovationCounter>>, config_cell: Rc<RefCell<GenomeConfig>>) -> Genome {
+    pub fn new(counter: &mut InnovationCounter, config: &GenomeConfig) -> Result<Genome, NeatError> {
+        Genome::new_with_rng(counter, config, &mut thread_rng())
+    }
+
+    pub fn new_with_rng<R>(counter: &mut InnovationCounter, config: &GenomeConfig, rng: &mut R) -> Result<Genome, NeatError>
+    where
+        R: Rng + ?Sized
+    {
         let mut neurons: HashVec<u32, ComparableGeneInterface<NeuronGene>> = HashVec::new();
         let mut connections: HashVec<u32, ComparableGeneInterface<ConnectionGene>> = HashVec::new();
-        
-        {
-            let config = config_cell.borrow();
 
+        {
             let mut i = 0;
 
             while i < config.get_n_sensor() {
-                let neuron = NeuronGene::new(i, neuron_gene::SENSOR);
+                let neuron = create_neuron(i, neuron_gene::SENSOR, config);
                 neurons.insert_ordered(i, ComparableGeneInterface(neuron));
                 i += 1;
             }
             while i < config.get_n_output() + config.get_n_sensor() {
-                let neuron = NeuronGene::new(i, neuron_gene::OUTPUT);
+                let neuron = create_neuron(i, neuron_gene::OUTPUT, config);
                 neurons.insert_ordered(i, ComparableGeneInterface(neuron));
                 i += 1
             }
@@ -48,9 +85,8 @@ impl Genome {
             if config.is_connected() {
                 for i in 0..config.get_n_sensor() {
                     for k in config.get_n_sensor()..(config.get_n_output() + config.get_n_sensor()) {
-                        let mut counter_mut = counter.borrow_mut();
-                        let innovation = counter_mut.get_connection_innovation(i, k);
-                        let connection = ConnectionGene::new(innovation, i, k, config.get_weight());
+                        let innovation = counter.get_connection_innovation(i, k)?;
+                        let connection = ConnectionGene::new(innovation, i, k, config.get_weight_with(rng));
 
                         connections.insert_ordered(innovation, ComparableGeneInterface(connection));
                     }
@@ -58,83 +94,233 @@ impl Genome {
             }
         }
 
-        Genome {
-            counter,
+        Ok(Genome {
             connections,
             neurons,
-            config: config_cell,
+        })
+    }
+
+    pub fn from_genes<N, C>(neurons: N, connections: C) -> Result<Genome, NeatError>
+    where
+        N: IntoIterator<Item = NeuronGene>,
+        C: IntoIterator<Item = ConnectionGene>
+    {
+        let neurons: Vec<NeuronGene> = neurons.into_iter().collect();
+        let connections: Vec<ConnectionGene> = connections.into_iter().collect();
+
+        let mut innovations = HashSet::new();
+        for innovation in neurons.iter().map(Gene::get_innovation_number).chain(connections.iter().map(Gene::get_innovation_number)) {
+            if !innovations.insert(innovation) {
+                return Err(NeatError::DuplicateInnovation(innovation));
+            }
+        }
+
+        if let Some(neuron) = neurons.iter().find(|neuron| !(neuron.get_time_constant() > 0.0 && neuron.get_time_constant().is_finite())) {
+            return Err(NeatError::TimeConstant { neuron: neuron.get_innovation_number(), value: neuron.get_time_constant() });
+        }
+
+        let neuron_ids: HashSet<u32> = neurons.iter().map(Gene::get_innovation_number).collect();
+        let mut pairs = HashSet::new();
+        for connection in connections.iter() {
+            for &neuron in &[connection.get_neuron_in(), connection.get_neuron_out()] {
+                if !neuron_ids.contains(&neuron) {
+                    return Err(NeatError::DanglingNeuron { connection: connection.get_innovation_number(), neuron });
+                }
+            }
+            if !pairs.insert((connection.get_neuron_in(), connection.get_neuron_out())) {
+                return Err(NeatError::DuplicateConnection {
+                    neuron_in: connection.get_neuron_in(),
+                    neuron_out: connection.get_neuron_out(),
+                });
+            }
+        }
+
+        Ok(Genome::from_genes_unchecked(neurons, connections))
+    }
+
+    pub(crate) fn from_genes_unchecked<N, C>(neurons: N, connections: C) -> Genome
+    where
+        N: IntoIterator<Item = NeuronGene>,
+        C: IntoIterator<Item = ConnectionGene>
+    {
+        let mut genome = Genome {
+            connections: HashVec::new(),
+            neurons: HashVec::new(),
+        };
+        for neuron in neurons {
+            genome.neurons.insert_ordered(neuron.get_innovation_number(), ComparableGeneInterface(neuron));
+        }
+        for connection in connections {
+            genome.connections.insert_ordered(connection.get_innovation_number(), ComparableGeneInterface(connection));
         }
+        genome
     }
 
-    pub fn crossover(_gen1: &Genome, _gen2: &Genome) -> Genome {
-        unimplemented!();
+    pub fn crossover(gen1: &Genome, gen2: &Genome, config: &GenomeConfig) -> Result<Genome, NeatError> {
+        Genome::crossover_with_rng(gen1, gen2, config, &mut thread_rng())
     }
 
-    pub fn distance(&self, _gen2: &Genome) -> f64 {
-        unimplemented!();
+    // Both parents must come from the same innovation counter, otherwise a shared
+    // innovation number can describe two unrelated connections.
+    pub fn crossover_with_rng<R>(gen1: &Genome, gen2: &Genome, config: &GenomeConfig, rng: &mut R) -> Result<Genome, NeatError>
+    where
+        R: Rng + ?Sized
+    {
+        let mut connections: HashVec<u32, ComparableGeneInterface<ConnectionGene>> = HashVec::new();
+
+        for (innovation, connection) in gen1.connections.iter_pairs() {
+            let child = match gen2.connections.get(innovation) {
+                Some(other) => {
+                    if (other.get_neuron_in(), other.get_neuron_out()) != (connection.get_neuron_in(), connection.get_neuron_out()) {
+                        return Err(NeatError::IncompatibleGenomes { innovation });
+                    }
+                    let mut child = if rng.gen::<bool>() {
+                        other.clone()
+                    } else {
+                        connection.clone()
+                    };
+                    if !connection.is_enabled() || !other.is_enabled() {
+                        child.set_enabled(rng.gen::<f64>() > 0.75);
+                    }
+                    child
+                },
+                None => connection.clone()
+            };
+            connections.insert(innovation, child);
+        }
+
+        // Matching neurons inherit their bias and time constant from either parent.
+        let mut neurons = gen1.neurons.clone();
+        for neuron in neurons.iter_mut() {
+            if let Some(other) = gen2.neurons.get(neuron.get_innovation_number()) {
+                let differs = other.get_bias() != neuron.get_bias() || other.get_time_constant() != neuron.get_time_constant();
+                if differs && rng.gen::<bool>() {
+                    neuron.set_bias(other.get_bias());
+                    neuron.set_time_constant(other.get_time_constant());
+                }
+            }
+        }
+
+        let child = Genome {
+            connections,
+            neurons,
+        };
+        child.debug_validate(config, "crossover");
+        Ok(child)
     }
 
-    pub fn mutate(&mut self) {
-        let mut rng = thread_rng();
-        let value: f64 = rng.gen();
-        let (mcc, mcn, muw, msw, mtc) = {
-            let config: &GenomeConfig = &self.config.borrow();
-            (config.get_mutate_create_connection(), config.get_mutate_create_neuron(), config.get_mutate_update_weight(), config.get_mutate_set_weight(), config.get_mutate_create_connection())
+    pub fn distance(&self, gen2: &Genome, config: &GenomeConfig) -> f64 {
+        let mut disjoint = 0;
+        let mut matching = 0;
+        let mut weight_difference = 0.0;
+
+        for (innovation, connection) in self.connections.iter_pairs() {
+            match gen2.connections.get(innovation) {
+                Some(other) => {
+                    matching += 1;
+                    weight_difference += (connection.get_weight() - other.get_weight()).abs();
+                },
+                None => disjoint += 1
+            }
+        }
+        disjoint += gen2.connections.keys().filter(|&innovation| !self.connections.contains(innovation)).count();
+
+        let size = self.connections.len().max(gen2.connections.len()).max(1) as f64;
+        let weight_difference = if matching > 0 {
+            weight_difference / matching as f64
+        } else {
+            0.0
         };
-        
-        if value > mcc {
-            self.mutate_create_connection(&mut rng);
+
+        config.get_compatibility_disjoint_coefficient() * disjoint as f64 / size
+            + config.get_compatibility_weight_coefficient() * weight_difference
+    }
+
+    pub fn mutate(&mut self, counter: &mut InnovationCounter, config: &GenomeConfig) -> Result<(), NeatError> {
+        self.mutate_with_rng(counter, config, &mut thread_rng())
+    }
+
+    pub fn mutate_with_rng<R>(&mut self, counter: &mut InnovationCounter, config: &GenomeConfig, rng: &mut R) -> Result<(), NeatError>
+    where
+        R: Rng + ?Sized
+    {
+        if rng.gen::<f64>() < config.get_mutate_create_connection() {
+            self.mutate_create_connection(rng, counter, config)?;
         }
-        if value > mcn {
-            self.mutate_create_neuron(&mut rng);
+        if rng.gen::<f64>() < config.get_mutate_create_neuron() {
+            self.mutate_create_neuron(rng, counter, config)?;
         }
-        if value > muw {
-            self.mutate_update_weight(&mut rng);
+        if rng.gen::<f64>() < config.get_mutate_update_weight() {
+            self.mutate_update_weight(rng, config);
         }
-        if value > msw {
-            self.mutate_set_weight(&mut rng);
+        if rng.gen::<f64>() < config.get_mutate_set_weight() {
+            self.mutate_set_weight(rng, config);
         }
-        if value > mtc {
-            self.mutate_toggle_connection(&mut rng);
+        if rng.gen::<f64>() < config.get_mutate_toggle_connection() {
+            self.mutate_toggle_connection(rng);
         }
+        // Checked first so configs without neuron mutations keep the same random stream.
+        if config.get_mutate_bias() > 0.0 && rng.gen::<f64>() < config.get_mutate_bias() {
+            self.mutate_bias(rng, config);
+        }
+        if config.get_mutate_time_constant() > 0.0 && rng.gen::<f64>() < config.get_mutate_time_constant() {
+            self.mutate_time_constant(rng, config);
+        }
+        self.debug_validate(config, "mutation");
+        Ok(())
     }
 
-    fn mutate_create_neuron(&mut self, rng: &mut ThreadRng) {
-        let sample = sample(rng, self.connections.len(), 1);
-        let mut iter_connections_index = sample.iter();
-        let mut counter = self.counter.borrow_mut();
-        
-        let index_connection = match iter_connections_index.next() {
-            Some(index) => index,
-            None => return,
+    fn mutate_create_neuron<R>(&mut self, rng: &mut R, counter: &mut InnovationCounter, config: &GenomeConfig) -> Result<(), NeatError>
+    where
+        R: Rng + ?Sized
+    {
+        let enabled: Vec<usize> = self.connections.iter()
+            .enumerate()
+            .filter(|(_, connection)| connection.is_enabled())
+            .map(|(index, _)| index)
+            .collect();
+
+        let (index_connection, old_connection) = match enabled.choose(rng).and_then(|&index| Some((index, self.connections.get_index(index)?))) {
+            Some(chosen) => chosen,
+            None => return Ok(()),
         };
 
-        #[allow(unused_mut)]
-        let mut old_connection = &mut self.connections[index_connection];
-        old_connection.toggle_enabled();
-
+        let innovation = old_connection.get_innovation_number();
         let neuron_in_innovation = old_connection.get_neuron_in();
-        let neuron_out_innovation = old_connection.get_neuron_in();
-
-        let neuron_in: &NeuronGene = self.neurons.get(neuron_in_innovation).unwrap();
-        let neuron_out: &NeuronGene = self.neurons.get(neuron_out_innovation).unwrap();
+        let neuron_out_innovation = old_connection.get_neuron_out();
+        let old_weight = old_connection.get_weight();
 
-        let neuron = NeuronGene::new(
-            counter.get_neuron_innovation(),
-            (neuron_in.get_class() + neuron_out.get_class())/2
+        let (neuron_in, neuron_out) = match (self.neurons.get(neuron_in_innovation), self.neurons.get(neuron_out_innovation)) {
+            (Some(neuron_in), Some(neuron_out)) => (neuron_in.get_class(), neuron_out.get_class()),
+            (None, _) => return Err(NeatError::DanglingNeuron { connection: innovation, neuron: neuron_in_innovation }),
+            (_, None) => return Err(NeatError::DanglingNeuron { connection: innovation, neuron: neuron_out_innovation }),
+        };
+        // The new neuron's class sits strictly between its neighbours, so adjacent
+        // classes (e.g. a sensor feeding class 2) leave no room for a split.
+        if neuron_out <= neuron_in || neuron_out - neuron_in < 2 {
+            return Ok(());
+        }
+        let neuron = create_neuron(
+            counter.get_neuron_innovation()?,
+            ((neuron_in as u64 + neuron_out as u64) / 2) as u32,
+            config
         );
         let new_in_connection = ConnectionGene::new(
-            counter.get_connection_innovation(neuron_in_innovation, neuron.get_innovation_number()),
+            counter.get_connection_innovation(neuron_in_innovation, neuron.get_innovation_number())?,
             neuron_in_innovation,
             neuron.get_innovation_number(),
             1.0
         );
         let new_out_connection = ConnectionGene::new(
-            counter.get_connection_innovation(neuron.get_innovation_number(), neuron_out_innovation),
+            counter.get_connection_innovation(neuron.get_innovation_number(), neuron_out_innovation)?,
             neuron.get_innovation_number(),
             neuron_out_innovation,
-            self.connections[index_connection].get_weight()    
+            old_weight
         );
+        // Disabled only once every innovation is allocated, so a full counter leaves the genome untouched.
+        if let Some(old_connection) = self.connections.get_index_mut(index_connection) {
+            old_connection.set_enabled(false);
+        }
 
         self.neurons.insert_ordered(
             neuron.get_innovation_number(), 
@@ -148,86 +334,164 @@ impl Genome {
             new_out_connection.get_innovation_number(),
             ComparableGeneInterface(new_out_connection)
         );
+        Ok(())
+    }
+
+    fn random_connection<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<usize> {
+        if self.connections.is_empty() {
+            return None;
+        }
+        sample(rng, self.connections.len(), 1).iter().next()
     }
 
-    fn mutate_update_weight(&mut self, rng: &mut ThreadRng) {
-        let index = match sample(rng, self.connections.len(), 1).iter().next() {
+    // Sensors only carry inputs, so their bias and time constant are never used.
+    fn random_neuron<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<&mut NeuronGene> {
+        let candidates: Vec<u32> = self.neurons.iter()
+            .filter(|neuron| neuron.get_class() != neuron_gene::SENSOR)
+            .map(|neuron| neuron.get_innovation_number())
+            .collect();
+        let &innovation = candidates.choose(rng)?;
+        self.neurons.get_mut(innovation).map(|neuron| &mut neuron.0)
+    }
+
+    fn mutate_bias<R: Rng + ?Sized>(&mut self, rng: &mut R, config: &GenomeConfig) {
+        let deviation = config.get_bias_deviation();
+        let delta = rng.gen_range(-1.0, 1.0) * deviation;
+        if let Some(neuron) = self.random_neuron(rng) {
+            neuron.set_bias(config.clamp_bias(neuron.get_bias() + delta));
+        }
+    }
+
+    fn mutate_time_constant<R: Rng + ?Sized>(&mut self, rng: &mut R, config: &GenomeConfig) {
+        let deviation = config.get_time_constant_deviation();
+        let delta = rng.gen_range(-1.0, 1.0) * deviation;
+        if let Some(neuron) = self.random_neuron(rng) {
+            neuron.set_time_constant(config.clamp_time_constant(neuron.get_time_constant() + delta));
+        }
+    }
+
+    fn mutate_update_weight<R: Rng + ?Sized>(&mut self, rng: &mut R, config: &GenomeConfig) {
+        let index = match self.random_connection(rng) {
             Some(index) => index,
             None => return
         };
 
-        let new_weight = self.connections[index].get_weight()*0.8 + 0.2 * (self.connections[index].get_weight() + 0.1) * rng.gen::<f64>();
-        self.connections[index].set_weight(new_weight);
+        let factor = rng.gen::<f64>();
+        if let Some(connection) = self.connections.get_index_mut(index) {
+            let new_weight = connection.get_weight()*0.8 + 0.2 * (connection.get_weight() + 0.1) * factor;
+            connection.set_weight(config.clamp_weight(new_weight));
+        }
     }
 
-    fn mutate_set_weight(&mut self, rng: &mut ThreadRng) {
-        let index = if let Some(index) = sample(rng, self.connections.len(), 1).iter().next() {
+    fn mutate_set_weight<R: Rng + ?Sized>(&mut self, rng: &mut R, config: &GenomeConfig) {
+        let index = if let Some(index) = self.random_connection(rng) {
             index
         } else {
             return;
         };
-        
-        let config = self.config.borrow();
 
-        self.connections[index].set_weight(config.get_weight());
+        let weight = config.get_weight_with(rng);
+        if let Some(connection) = self.connections.get_index_mut(index) {
+            connection.set_weight(weight);
+        }
     }
 
-    fn mutate_toggle_connection(&mut self, rng: &mut ThreadRng) {
-        let index = if let Some(index) = sample(rng, self.connections.len(), 1).iter().next() {
+    fn mutate_toggle_connection<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let index = if let Some(index) = self.random_connection(rng) {
             index
         } else {
             return;
         };
 
-        self.connections[index].toggle_enabled();
+        if let Some(connection) = self.connections.get_index_mut(index) {
+            connection.toggle_enabled();
+        }
     }
 
-    fn mutate_create_connection(&mut self, rng: &mut ThreadRng) {
-        for _ in 0..100 {    
-            let sample_rng = sample(rng, self.neurons.len(), 2);
-            let mut sample_iter = sample_rng.iter();
+    fn mutate_create_connection<R>(&mut self, rng: &mut R, counter: &mut InnovationCounter, config: &GenomeConfig) -> Result<(), NeatError>
+    where
+        R: Rng + ?Sized
+    {
+        if self.neurons.len() < 2 {
+            return Ok(());
+        }
 
-            let neur1 = match sample_iter.next() {
-                Some(index) => index,
-                None => return
+        for _ in 0..100 {
+            let sample_rng = sample(rng, self.neurons.len(), 2);
+            let (neur1, neur2) = match (self.neurons.get_index(sample_rng.index(0)), self.neurons.get_index(sample_rng.index(1))) {
+                (Some(neur1), Some(neur2)) => (neur1, neur2),
+                _ => return Ok(())
             };
 
-            let connection = loop {
-                let neur2 = match sample_iter.next() {
-                    Some(index) => index,
-                    None => return
-                };
-                match self.neurons[neur1].get_class().cmp(&self.neurons[neur2].get_class()) {
-                    Ordering::Equal => continue,
-                    Ordering::Less => {
-                        let mut counter = self.counter.borrow_mut();
-                        let config = self.config.borrow();
-                        break ConnectionGene::new(counter.get_connection_innovation(neur1 as u32, neur2 as u32), neur1 as u32, neur2 as u32, config.get_weight());
-                    },
-                    Ordering::Greater => {
-                        let mut counter = self.counter.borrow_mut();
-                        let config = self.config.borrow();
-                        break ConnectionGene::new(counter.get_connection_innovation(neur2 as u32, neur1 as u32), neur2 as u32, neur1 as u32, config.get_weight());
-                    }
-                };
+            let (neuron_in, neuron_out) = match neur1.get_class().cmp(&neur2.get_class()) {
+                Ordering::Equal => continue,
+                Ordering::Less => (neur1.get_innovation_number(), neur2.get_innovation_number()),
+                Ordering::Greater => (neur2.get_innovation_number(), neur1.get_innovation_number())
             };
+
+            let exists = self.connections.iter().any(|connection| {
+                connection.get_neuron_in() == neuron_in && connection.get_neuron_out() == neuron_out
+            });
+            // Class order alone does not rule out cycles once a seed links hidden
+            // neurons against it, so feed-forward genomes also check reachability.
+            if exists || (config.is_feed_forward() && self.reaches(neuron_out, neuron_in)) {
+                continue;
+            }
+
+            let connection = ConnectionGene::new(counter.get_connection_innovation(neuron_in, neuron_out)?, neuron_in, neuron_out, config.get_weight_with(rng));
             self.connections.insert_ordered(connection.get_innovation_number(), ComparableGeneInterface(connection));
+            return Ok(());
+        }
+        Ok(())
+    }
+
+    // Whether a path of connections, enabled or not, leads from `from` to `to`.
+    fn reaches(&self, from: u32, to: u32) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending = vec![from];
+        while let Some(neuron) = pending.pop() {
+            if neuron == to {
+                return true;
+            }
+            if visited.insert(neuron) {
+                pending.extend(self.connections.iter()
+                    .filter(|connection| connection.get_neuron_in() == neuron)
+                    .map(|connection| connection.get_neuron_out()));
+            }
         }
+        false
+    }
+
+    pub fn get_connection(&self, innovation: u32) -> Option<&ConnectionGene> {
+        self.connections.get(innovation).map(|connection| &connection.0)
     }
 
-    pub fn iter_connections(&self) -> Iter<ComparableGeneInterface<ConnectionGene>> {
+    pub fn get_neuron(&self, innovation: u32) -> Option<&NeuronGene> {
+        self.neurons.get(innovation).map(|neuron| &neuron.0)
+    }
+
+    pub fn iter_connections(&self) -> Iter<'_, ComparableGeneInterface<ConnectionGene>> {
         self.connections.iter()
     }
 
-    pub fn iter_neurons(&self) -> Iter<ComparableGeneInterface<NeuronGene>> {
+    pub fn iter_neurons(&self) -> Iter<'_, ComparableGeneInterface<NeuronGene>> {
         self.neurons.iter()
     }
 }
 
+// New neurons start from the gene defaults pulled into the configured bounds.
+fn create_neuron(innovation: u32, class: u32, config: &GenomeConfig) -> NeuronGene {
+    let mut neuron = NeuronGene::new(innovation, class);
+    neuron.set_bias(config.clamp_bias(neuron.get_bias()));
+    neuron.set_time_constant(config.clamp_time_constant(neuron.get_time_constant()));
+    neuron
+}
+
 pub trait Gene {
     fn get_innovation_number(&self) -> u32;
 }
 
+#[derive(Clone, Debug)]
 pub struct ComparableGeneInterface<T>(T)
     where T: Gene;
 
@@ -291,9 +555,9 @@ mod tests {
 
     #[test]
     fn test_genome_new() {
-        let config = Rc::new(RefCell::new(GenomeConfig::new(2, 2)));
-        let counter = Rc::new(RefCell::new(InnovationCounter::new(4)));
-        let genome = Genome::new(Rc::clone(&counter), Rc::clone(&config));
+        let mut config = GenomeConfig::new(2, 2);
+        let mut counter = InnovationCounter::new(4);
+        let genome = Genome::new(&mut counter, &config).unwrap();
 
         let control = &[0, 1, 2, 3];
 
@@ -311,12 +575,108 @@ mod tests {
 
         assert_eq!(genome.iter_connections().count(), 0);
 
-        {
-            config.borrow_mut().set_is_connected(true);
-        }
+        config.set_is_connected(true);
 
-        let genome = Genome::new(Rc::clone(&counter), Rc::clone(&config));
+        let genome = Genome::new(&mut counter, &config).unwrap();
 
         assert_eq!(genome.iter_connections().count(), 4);
     }
+
+    #[test]
+    fn test_create_connection_keeps_feed_forward() {
+        // Hidden 20 feeds hidden 10, against the class order new connections follow.
+        let seed: Genome = "neuron 0 sensor\nneuron 1 output\nneuron 2 hidden 20\nneuron 3 hidden 10\n\
+                            connection 4 0 2 1 enabled\nconnection 5 2 3 1 enabled\nconnection 6 3 1 1 enabled".parse().unwrap();
+        let config = GenomeConfig::new(1, 1);
+        assert!(seed.validate_with(&config).is_ok());
+
+        let mut counter = InnovationCounter::new(2);
+        counter.register_genome(&seed).unwrap();
+        let mut rng = thread_rng();
+        for _ in 0..50 {
+            let mut genome = seed.clone();
+            genome.mutate_create_connection(&mut rng, &mut counter, &config).unwrap();
+            assert!(genome.validate_with(&config).is_ok());
+            assert!(genome.iter_connections().all(|connection| (connection.get_neuron_in(), connection.get_neuron_out()) != (3, 2)));
+        }
+    }
+
+    #[test]
+    fn test_new_neurons_respect_bounds() {
+        let mut config = GenomeConfig::new(1, 1);
+        config.set_is_connected(true);
+        config.set_bias_min_value(0.5);
+        config.set_bias_max_value(1.0);
+        config.set_time_constant_min_value(2.0);
+        config.set_time_constant_max_value(5.0);
+        let mut counter = InnovationCounter::new(2);
+        let mut genome = Genome::new(&mut counter, &config).unwrap();
+        genome.mutate_create_neuron(&mut thread_rng(), &mut counter, &config).unwrap();
+
+        for neuron in genome.iter_neurons() {
+            assert_eq!((neuron.get_bias(), neuron.get_time_constant()), (0.5, 2.0));
+        }
+        assert_eq!(genome.iter_neurons().count(), 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_genome_serde() {
+        let mut config = GenomeConfig::new(2, 2);
+        config.set_is_connected(true);
+        let mut counter = InnovationCounter::new(4);
+        let mut genome = Genome::new(&mut counter, &config).unwrap();
+        genome.mutate_create_neuron(&mut thread_rng(), &mut counter, &config).unwrap();
+
+        let json = serde_json::to_string(&genome).unwrap();
+        let restored: Genome = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+        assert_eq!(restored.iter_neurons().count(), 5);
+        assert_eq!(restored.iter_connections().count(), 6);
+
+        let config_json = serde_json::to_string(&config).unwrap();
+        let restored_config: GenomeConfig = serde_json::from_str(&config_json).unwrap();
+        assert!(restored_config.is_connected());
+
+        let counter_json = serde_json::to_string(&counter).unwrap();
+        let mut restored_counter: InnovationCounter = serde_json::from_str(&counter_json).unwrap();
+        assert_eq!(restored_counter.get_connection_innovation(0, 2).unwrap(), counter.get_connection_innovation(0, 2).unwrap());
+        assert_eq!(restored_counter.get_neuron_innovation().unwrap(), counter.get_neuron_innovation().unwrap());
+    }
+
+    #[test]
+    fn test_malformed_genomes() {
+        let neurons = || vec![NeuronGene::new(0, neuron_gene::SENSOR), NeuronGene::new(1, neuron_gene::OUTPUT)];
+
+        assert!(matches!(
+            Genome::from_genes(neurons(), vec![ConnectionGene::new(2, 0, 7, 1.0)]),
+            Err(NeatError::DanglingNeuron { connection: 2, neuron: 7 })
+        ));
+        assert!(matches!(
+            Genome::from_genes(neurons(), vec![ConnectionGene::new(1, 0, 1, 1.0)]),
+            Err(NeatError::DuplicateInnovation(1))
+        ));
+        assert!(matches!(
+            Genome::from_genes(neurons(), vec![ConnectionGene::new(2, 0, 1, 1.0), ConnectionGene::new(3, 0, 1, 1.0)]),
+            Err(NeatError::DuplicateConnection { neuron_in: 0, neuron_out: 1 })
+        ));
+
+        let mut extended = neurons();
+        extended.push(NeuronGene::new(2, neuron_gene::SENSOR));
+        let gen1 = Genome::from_genes(neurons(), vec![ConnectionGene::new(3, 0, 1, 1.0)]).unwrap();
+        let gen2 = Genome::from_genes(extended, vec![ConnectionGene::new(3, 2, 1, 1.0)]).unwrap();
+        let config = GenomeConfig::new(1, 1);
+        assert!(matches!(Genome::crossover(&gen1, &gen2, &config), Err(NeatError::IncompatibleGenomes { innovation: 3 })));
+        assert!(Genome::crossover(&gen1, &gen1, &config).is_ok());
+    }
+
+    #[test]
+    fn test_genome_auto_traits() {
+        fn assert_traits<T: Send + Sync + Clone>() {}
+
+        assert_traits::<Genome>();
+        assert_traits::<InnovationCounter>();
+        assert_traits::<GenomeConfig>();
+    }
 }
\ No newline at end of file
@@ -1,13 +1,24 @@
 #![allow(dead_code)]
 
 use super::Gene;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
 
 pub const SENSOR: u32 = 1;
-pub const OUTPUT: u32 = std::u32::MAX;
+pub const OUTPUT: u32 = u32::MAX;
+pub const DEFAULT_TIME_CONSTANT: f64 = 1.0;
 
+// The bias and time constant only drive CtrnnNetwork; the feed-forward and
+// recurrent networks and every exporter sum their inputs without them.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NeuronGene {
     innovation_number: u32,
     class: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    bias: f64,
+    #[cfg_attr(feature = "serde", serde(default = "default_time_constant"))]
+    time_constant: f64,
 }
 
 impl NeuronGene {
@@ -15,12 +26,35 @@ impl NeuronGene {
         NeuronGene {
             innovation_number,
             class,
+            bias: 0.0,
+            time_constant: DEFAULT_TIME_CONSTANT,
         }
     }
 
     pub fn get_class(&self) -> u32 {
         self.class
     }
+
+    pub fn get_bias(&self) -> f64 {
+        self.bias
+    }
+
+    pub fn get_time_constant(&self) -> f64 {
+        self.time_constant
+    }
+
+    pub fn set_bias(&mut self, bias: f64) {
+        self.bias = bias;
+    }
+
+    pub fn set_time_constant(&mut self, time_constant: f64) {
+        self.time_constant = time_constant;
+    }
+}
+
+#[cfg(feature = "serde")]
+fn default_time_constant() -> f64 {
+    DEFAULT_TIME_CONSTANT
 }
 
 impl Gene for NeuronGene {
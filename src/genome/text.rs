@@ -0,0 +1,291 @@
+use std::collections::{ HashMap, HashSet };
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use super::{ Genome, Gene };
+use super::connection_gene::ConnectionGene;
+use super::neuron_gene::{ self, NeuronGene };
+
+// One gene per line, `#` starts a comment:
+//   neuron <innovation> <sensor|hidden|output> [class] [bias <value>] [time_constant <value>]
+//   connection <innovation> <in> <out> <weight> <enabled|disabled>
+impl fmt::Display for Genome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for neuron in self.iter_neurons() {
+            write!(
+                f,
+                "neuron {} {} {}",
+                neuron.get_innovation_number(), neuron_type(neuron.get_class()), neuron.get_class()
+            )?;
+            if neuron.get_bias() != 0.0 {
+                write!(f, " bias {}", neuron.get_bias())?;
+            }
+            if neuron.get_time_constant() != neuron_gene::DEFAULT_TIME_CONSTANT {
+                write!(f, " time_constant {}", neuron.get_time_constant())?;
+            }
+            writeln!(f)?;
+        }
+        for connection in self.iter_connections() {
+            writeln!(
+                f,
+                "connection {} {} {} {} {}",
+                connection.get_innovation_number(),
+                connection.get_neuron_in(),
+                connection.get_neuron_out(),
+                connection.get_weight(),
+                if connection.is_enabled() { "enabled" } else { "disabled" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn neuron_type(class: u32) -> &'static str {
+    match class {
+        neuron_gene::SENSOR => "sensor",
+        neuron_gene::OUTPUT => "output",
+        _ => "hidden",
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseGenomeError {
+    Syntax { line: usize, message: String },
+    DuplicateInnovation { line: usize, innovation: u32 },
+    DuplicateConnection { line: usize, neuron_in: u32, neuron_out: u32 },
+    DanglingNeuron { line: usize, innovation: u32, neuron: u32 },
+}
+
+impl fmt::Display for ParseGenomeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGenomeError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            ParseGenomeError::DuplicateInnovation { line, innovation } => {
+                write!(f, "line {}: duplicate innovation number {}", line, innovation)
+            },
+            ParseGenomeError::DuplicateConnection { line, neuron_in, neuron_out } => {
+                write!(f, "line {}: duplicate connection {} -> {}", line, neuron_in, neuron_out)
+            },
+            ParseGenomeError::DanglingNeuron { line, innovation, neuron } => {
+                write!(f, "line {}: connection {} references unknown neuron {}", line, innovation, neuron)
+            },
+        }
+    }
+}
+
+impl Error for ParseGenomeError {}
+
+impl FromStr for Genome {
+    type Err = ParseGenomeError;
+
+    fn from_str(s: &str) -> Result<Genome, ParseGenomeError> {
+        let mut innovations = HashSet::new();
+        let mut neurons = Vec::new();
+        let mut connections = Vec::new();
+
+        for (index, raw_line) in s.lines().enumerate() {
+            let line = index + 1;
+            let content = raw_line.split('#').next().unwrap_or("");
+            let tokens: Vec<&str> = content.split_whitespace().collect();
+            if tokens.is_empty() {
+                continue;
+            }
+
+            let syntax = |message: String| ParseGenomeError::Syntax { line, message };
+            let innovation: u32 = match tokens.get(1) {
+                Some(token) => token.parse().map_err(|_| syntax(format!("invalid innovation number {:?}", token)))?,
+                None => return Err(syntax("missing innovation number".to_string()))
+            };
+
+            match tokens[0] {
+                "neuron" => {
+                    let split = tokens.iter().skip(2).position(|&token| NEURON_PARAMETERS.contains(&token)).map_or(tokens.len(), |index| index + 2);
+                    let class = parse_neuron_class(&tokens[2..split]).map_err(syntax)?;
+                    if !innovations.insert(innovation) {
+                        return Err(ParseGenomeError::DuplicateInnovation { line, innovation });
+                    }
+                    let mut neuron = NeuronGene::new(innovation, class);
+                    parse_neuron_parameters(&tokens[split..], &mut neuron).map_err(syntax)?;
+                    neurons.push(neuron);
+                },
+                "connection" => {
+                    if tokens.len() != 6 {
+                        return Err(syntax("expected: connection <innovation> <in> <out> <weight> <enabled|disabled>".to_string()));
+                    }
+                    let neuron_in: u32 = tokens[2].parse().map_err(|_| syntax(format!("invalid neuron {:?}", tokens[2])))?;
+                    let neuron_out: u32 = tokens[3].parse().map_err(|_| syntax(format!("invalid neuron {:?}", tokens[3])))?;
+                    let weight: f64 = tokens[4].parse().map_err(|_| syntax(format!("invalid weight {:?}", tokens[4])))?;
+                    let enabled = match tokens[5] {
+                        "enabled" => true,
+                        "disabled" => false,
+                        other => return Err(syntax(format!("expected enabled or disabled, found {:?}", other)))
+                    };
+                    if !innovations.insert(innovation) {
+                        return Err(ParseGenomeError::DuplicateInnovation { line, innovation });
+                    }
+
+                    let mut connection = ConnectionGene::new(innovation, neuron_in, neuron_out, weight);
+                    connection.set_enabled(enabled);
+                    connections.push((line, connection));
+                },
+                other => return Err(syntax(format!("unknown gene kind {:?}", other)))
+            }
+        }
+
+        let neuron_ids: HashSet<u32> = neurons.iter().map(|neuron| neuron.get_innovation_number()).collect();
+        let mut pairs = HashMap::new();
+        for (line, connection) in connections.iter() {
+            let line = *line;
+            let innovation = connection.get_innovation_number();
+            for &neuron in &[connection.get_neuron_in(), connection.get_neuron_out()] {
+                if !neuron_ids.contains(&neuron) {
+                    return Err(ParseGenomeError::DanglingNeuron { line, innovation, neuron });
+                }
+            }
+            let pair = (connection.get_neuron_in(), connection.get_neuron_out());
+            if pairs.insert(pair, innovation).is_some() {
+                return Err(ParseGenomeError::DuplicateConnection { line, neuron_in: pair.0, neuron_out: pair.1 });
+            }
+        }
+
+        Ok(Genome::from_genes_unchecked(neurons, connections.into_iter().map(|(_, connection)| connection)))
+    }
+}
+
+fn parse_neuron_class(tokens: &[&str]) -> Result<u32, String> {
+    let class = match tokens.get(1) {
+        Some(token) => Some(token.parse::<u32>().map_err(|_| format!("invalid class {:?}", token))?),
+        None => None
+    };
+    if tokens.len() > 2 {
+        return Err("expected: neuron <innovation> <sensor|hidden|output> [class]".to_string());
+    }
+
+    match (tokens.first(), class) {
+        (Some(&"sensor"), None) | (Some(&"sensor"), Some(neuron_gene::SENSOR)) => Ok(neuron_gene::SENSOR),
+        (Some(&"output"), None) | (Some(&"output"), Some(neuron_gene::OUTPUT)) => Ok(neuron_gene::OUTPUT),
+        (Some(&"hidden"), Some(class)) if class > neuron_gene::SENSOR && class < neuron_gene::OUTPUT => Ok(class),
+        (Some(&"hidden"), None) => Err("hidden neurons need a class".to_string()),
+        (Some(kind), Some(class)) if ["sensor", "hidden", "output"].contains(kind) => {
+            Err(format!("class {} is not valid for a {} neuron", class, kind))
+        },
+        (Some(kind), _) => Err(format!("unknown neuron type {:?}", kind)),
+        (None, _) => Err("missing neuron type".to_string()),
+    }
+}
+
+const NEURON_PARAMETERS: &[&str] = &["bias", "time_constant"];
+
+fn parse_neuron_parameters(tokens: &[&str], neuron: &mut NeuronGene) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for pair in tokens.chunks(2) {
+        let value = match pair.get(1) {
+            Some(token) => token.parse::<f64>().map_err(|_| format!("invalid {} {:?}", pair[0], token))?,
+            None => return Err(format!("missing value for {}", pair[0]))
+        };
+        if !seen.insert(pair[0]) {
+            return Err(format!("{} is given more than once", pair[0]));
+        }
+        match pair[0] {
+            "bias" => neuron.set_bias(value),
+            "time_constant" if value > 0.0 && value.is_finite() => neuron.set_time_constant(value),
+            "time_constant" => return Err(format!("time constant {} is not positive", value)),
+            other => return Err(format!("unknown neuron parameter {:?}", other)),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ GenomeConfig, InnovationCounter };
+
+    const SEED: &str = "
+        # xor seed with one hidden neuron
+        neuron 0 sensor
+        neuron 1 sensor
+        neuron 2 output
+        neuron 3 hidden 2147483648
+        connection 4 0 3 1.5 enabled
+        connection 5 1 3 -0.25 enabled
+        connection 6 3 2 2 enabled
+        connection 7 0 2 0.5 disabled
+    ";
+
+    #[test]
+    fn test_parse_seed() {
+        let genome: Genome = SEED.parse().unwrap();
+
+        assert_eq!(genome.iter_neurons().count(), 4);
+        assert_eq!(genome.get_neuron(3).unwrap().get_class(), 2147483648);
+        assert_eq!(genome.get_connection(5).unwrap().get_weight(), -0.25);
+        assert!(!genome.get_connection(7).unwrap().is_enabled());
+
+        let text = genome.to_string();
+        assert!(text.starts_with("neuron 0 sensor 1\n"));
+        assert!(text.contains("connection 7 0 2 0.5 disabled\n"));
+        assert_eq!(text.parse::<Genome>().unwrap().to_string(), text);
+    }
+
+    #[test]
+    fn test_neuron_parameters() {
+        let genome: Genome = "neuron 0 sensor\nneuron 1 output bias -0.5 time_constant 0.25".parse().unwrap();
+        let output = genome.get_neuron(1).unwrap();
+        assert_eq!((output.get_bias(), output.get_time_constant()), (-0.5, 0.25));
+        assert!(genome.to_string().contains("neuron 1 output 4294967295 bias -0.5 time_constant 0.25\n"));
+
+        let hidden: Genome = "neuron 0 hidden 7 time_constant 2".parse().unwrap();
+        assert_eq!(hidden.get_neuron(0).unwrap().get_class(), 7);
+        assert_eq!(hidden.to_string(), "neuron 0 hidden 7 time_constant 2\n");
+
+        assert!("neuron 0 output bias".parse::<Genome>().is_err());
+        assert!("neuron 0 output time_constant 0".parse::<Genome>().is_err());
+        assert!("neuron 0 output bias 1 bias 2".parse::<Genome>().is_err());
+        assert!("neuron 0 output bias 1 gain 2".parse::<Genome>().is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut config = GenomeConfig::new(3, 2);
+        config.set_is_connected(true);
+        config.set_mutate_create_neuron(0.5);
+        config.set_mutate_bias(0.5);
+        config.set_mutate_time_constant(0.5);
+        let mut counter = InnovationCounter::new(5);
+        let mut genome = Genome::new(&mut counter, &config).unwrap();
+        for _ in 0..10 {
+            genome.mutate(&mut counter, &config).unwrap();
+        }
+
+        let text = genome.to_string();
+        assert_eq!(text.parse::<Genome>().unwrap().to_string(), text);
+    }
+
+    #[test]
+    fn test_validation() {
+        let dangling = "neuron 0 sensor\nneuron 1 output\nconnection 2 0 9 1 enabled";
+        assert_eq!(
+            dangling.parse::<Genome>().unwrap_err(),
+            ParseGenomeError::DanglingNeuron { line: 3, innovation: 2, neuron: 9 }
+        );
+
+        let duplicate = "neuron 0 sensor\nneuron 1 output\nconnection 1 0 1 1 enabled";
+        assert_eq!(
+            duplicate.parse::<Genome>().unwrap_err(),
+            ParseGenomeError::DuplicateInnovation { line: 3, innovation: 1 }
+        );
+
+        let pair = "neuron 0 sensor\nneuron 1 output\nconnection 2 0 1 1 enabled\nconnection 3 0 1 1 enabled";
+        assert_eq!(
+            pair.parse::<Genome>().unwrap_err(),
+            ParseGenomeError::DuplicateConnection { line: 4, neuron_in: 0, neuron_out: 1 }
+        );
+
+        let error = "neuron 0 hidden".parse::<Genome>().unwrap_err();
+        assert_eq!(error.to_string(), "line 1: hidden neurons need a class");
+        assert!("neuron 0 sensor 5".parse::<Genome>().is_err());
+        assert!("synapse 0 1 2".parse::<Genome>().is_err());
+        assert!("connection 0 1 2 heavy enabled".parse::<Genome>().is_err());
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::{ HashMap, HashSet };
+use std::fmt;
+use super::{ Genome, Gene };
+use super::genome_config::GenomeConfig;
+use super::neuron_gene;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    DanglingConnection { connection: u32, neuron: u32 },
+    DuplicateInnovation(u32),
+    DuplicateConnection { neuron_in: u32, neuron_out: u32 },
+    KeyMismatch { key: u32, innovation: u32 },
+    OutOfOrder { previous: u32, innovation: u32 },
+    SensorInput { connection: u32, neuron: u32 },
+    SensorCount { expected: u32, found: u32 },
+    OutputCount { expected: u32, found: u32 },
+    Cycle { neuron: u32 },
+    TimeConstant { neuron: u32, value: f64 },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::DanglingConnection { connection, neuron } => {
+                write!(f, "connection {} references missing neuron {}", connection, neuron)
+            },
+            Violation::DuplicateInnovation(innovation) => write!(f, "innovation {} is used by more than one gene", innovation),
+            Violation::DuplicateConnection { neuron_in, neuron_out } => {
+                write!(f, "connection {} -> {} appears more than once", neuron_in, neuron_out)
+            },
+            Violation::KeyMismatch { key, innovation } => write!(f, "gene {} is stored under key {}", innovation, key),
+            Violation::OutOfOrder { previous, innovation } => {
+                write!(f, "gene {} is stored after gene {}", innovation, previous)
+            },
+            Violation::SensorInput { connection, neuron } => {
+                write!(f, "connection {} feeds into sensor {}", connection, neuron)
+            },
+            Violation::SensorCount { expected, found } => write!(f, "expected {} sensors, found {}", expected, found),
+            Violation::OutputCount { expected, found } => write!(f, "expected {} outputs, found {}", expected, found),
+            Violation::Cycle { neuron } => write!(f, "neuron {} is part of a cycle in a feed-forward genome", neuron),
+            Violation::TimeConstant { neuron, value } => {
+                write!(f, "neuron {} has time constant {}, which is not positive", neuron, value)
+            },
+        }
+    }
+}
+
+impl Genome {
+    // Structural invariants that hold for any genome, whatever its config.
+    pub fn validate(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        check_order(self.neurons.keys().zip(self.iter_neurons().map(|neuron| neuron.get_innovation_number())), &mut violations);
+        check_order(self.connections.keys().zip(self.iter_connections().map(|connection| connection.get_innovation_number())), &mut violations);
+
+        let mut innovations = HashSet::new();
+        let neuron_innovations = self.iter_neurons().map(|neuron| neuron.get_innovation_number());
+        for innovation in neuron_innovations.chain(self.iter_connections().map(|connection| connection.get_innovation_number())) {
+            if !innovations.insert(innovation) {
+                violations.push(Violation::DuplicateInnovation(innovation));
+            }
+        }
+
+        for neuron in self.iter_neurons() {
+            let value = neuron.get_time_constant();
+            if !(value > 0.0 && value.is_finite()) {
+                violations.push(Violation::TimeConstant { neuron: neuron.get_innovation_number(), value });
+            }
+        }
+
+        let mut pairs = HashSet::new();
+        for connection in self.iter_connections() {
+            let innovation = connection.get_innovation_number();
+            for &neuron in &[connection.get_neuron_in(), connection.get_neuron_out()] {
+                if self.get_neuron(neuron).is_none() {
+                    violations.push(Violation::DanglingConnection { connection: innovation, neuron });
+                }
+            }
+            if let Some(neuron) = self.get_neuron(connection.get_neuron_out()) {
+                if neuron.get_class() == neuron_gene::SENSOR {
+                    violations.push(Violation::SensorInput { connection: innovation, neuron: connection.get_neuron_out() });
+                }
+            }
+            if !pairs.insert((connection.get_neuron_in(), connection.get_neuron_out())) {
+                violations.push(Violation::DuplicateConnection {
+                    neuron_in: connection.get_neuron_in(),
+                    neuron_out: connection.get_neuron_out(),
+                });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    // Adds the checks that depend on the config: sensor and output counts and,
+    // for feed-forward configs, that the connections (enabled or not) form no cycle.
+    pub fn validate_with(&self, config: &GenomeConfig) -> Result<(), Vec<Violation>> {
+        let mut violations = self.validate().err().unwrap_or_default();
+
+        let count = |class| self.iter_neurons().filter(|neuron| neuron.get_class() == class).count() as u32;
+        let sensors = count(neuron_gene::SENSOR);
+        if sensors != config.get_n_sensor() {
+            violations.push(Violation::SensorCount { expected: config.get_n_sensor(), found: sensors });
+        }
+        let outputs = count(neuron_gene::OUTPUT);
+        if outputs != config.get_n_output() {
+            violations.push(Violation::OutputCount { expected: config.get_n_output(), found: outputs });
+        }
+
+        if config.is_feed_forward() {
+            if let Some(neuron) = self.find_cycle() {
+                violations.push(Violation::Cycle { neuron });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    pub(crate) fn debug_validate(&self, config: &GenomeConfig, operation: &str) {
+        if cfg!(debug_assertions) {
+            if let Err(violations) = self.validate_with(config) {
+                let violations: Vec<String> = violations.iter().map(Violation::to_string).collect();
+                panic!("{} produced an invalid genome: {}", operation, violations.join(", "));
+            }
+        }
+    }
+
+    // Kahn's algorithm: any neuron left with incoming edges after the sort is on or behind a cycle.
+    fn find_cycle(&self) -> Option<u32> {
+        let mut incoming: HashMap<u32, usize> = self.neurons.keys().map(|neuron| (neuron, 0)).collect();
+        let mut outgoing: HashMap<u32, Vec<u32>> = HashMap::new();
+        for connection in self.iter_connections() {
+            *incoming.entry(connection.get_neuron_out()).or_insert(0) += 1;
+            outgoing.entry(connection.get_neuron_in()).or_default().push(connection.get_neuron_out());
+        }
+
+        let mut ready: Vec<u32> = incoming.iter().filter(|(_, &count)| count == 0).map(|(&neuron, _)| neuron).collect();
+        while let Some(neuron) = ready.pop() {
+            for next in outgoing.get(&neuron).into_iter().flatten() {
+                if let Some(count) = incoming.get_mut(next) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push(*next);
+                    }
+                }
+            }
+        }
+
+        incoming.into_iter().filter(|&(_, count)| count > 0).map(|(neuron, _)| neuron).min()
+    }
+}
+
+fn check_order<I: Iterator<Item = (u32, u32)>>(entries: I, violations: &mut Vec<Violation>) {
+    let mut previous: Option<u32> = None;
+    for (key, innovation) in entries {
+        if key != innovation {
+            violations.push(Violation::KeyMismatch { key, innovation });
+        }
+        if let Some(previous) = previous {
+            if innovation <= previous {
+                violations.push(Violation::OutOfOrder { previous, innovation });
+            }
+        }
+        previous = Some(innovation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InnovationCounter;
+
+    #[test]
+    fn test_valid_genomes() {
+        let mut config = GenomeConfig::new(3, 2);
+        config.set_is_connected(true);
+        config.set_mutate_create_neuron(0.5);
+        config.set_mutate_create_connection(0.5);
+        config.set_mutate_time_constant(0.5);
+        config.set_time_constant_deviation(5.0);
+        let mut counter = InnovationCounter::new(5);
+
+        let mut genome = Genome::new(&mut counter, &config).unwrap();
+        for _ in 0..50 {
+            genome.mutate(&mut counter, &config).unwrap();
+            assert_eq!(genome.validate_with(&config), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_violations() {
+        let genome: Genome = "
+            neuron 0 sensor
+            neuron 1 output
+            neuron 2 hidden 10
+            neuron 3 hidden 20
+            connection 4 1 0 1 enabled
+            connection 5 2 3 1 enabled
+            connection 6 3 2 1 disabled
+        ".parse().unwrap();
+
+        assert_eq!(genome.validate(), Err(vec![Violation::SensorInput { connection: 4, neuron: 0 }]));
+
+        let mut config = GenomeConfig::new(2, 1);
+        let violations = genome.validate_with(&config).unwrap_err();
+        assert!(violations.contains(&Violation::SensorCount { expected: 2, found: 1 }));
+        assert!(violations.contains(&Violation::Cycle { neuron: 2 }));
+
+        config.set_feed_forward(false);
+        assert!(!genome.validate_with(&config).unwrap_err().iter().any(|violation| matches!(violation, Violation::Cycle { .. })));
+    }
+
+    #[test]
+    fn test_storage_order() {
+        let mut genome = Genome::from_genes(
+            vec![neuron_gene::NeuronGene::new(0, neuron_gene::SENSOR), neuron_gene::NeuronGene::new(1, neuron_gene::OUTPUT)],
+            vec![]
+        ).unwrap();
+        genome.neurons.insert(7, genome.neurons.get(0).unwrap().clone());
+
+        let violations = genome.validate().unwrap_err();
+        assert!(violations.contains(&Violation::KeyMismatch { key: 7, innovation: 0 }));
+        assert!(violations.contains(&Violation::OutOfOrder { previous: 1, innovation: 0 }));
+        assert!(violations.contains(&Violation::DuplicateInnovation(0)));
+
+        genome.neurons.get_mut(1).unwrap().set_time_constant(0.0);
+        assert!(genome.validate().unwrap_err().contains(&Violation::TimeConstant { neuron: 1, value: 0.0 }));
+    }
+}
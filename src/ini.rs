@@ -0,0 +1,527 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use crate::{ ConfigError, Criteria, FitnessCriterion, GenomeConfig, Neat, NeatConfig };
+
+const NEAT: &str = "NEAT";
+const GENOME: &str = "DefaultGenome";
+const REPRODUCTION: &str = "DefaultReproduction";
+const SPECIES_SET: &str = "DefaultSpeciesSet";
+const STAGNATION: &str = "DefaultStagnation";
+
+// Keys neat-python understands but that have no counterpart here. They are
+// accepted so existing files load, and otherwise ignored. The bias keys belong
+// here too: neat-python applies the bias in every network, while here it only
+// drives CtrnnNetwork, so mapping them would spend mutations on genes that the
+// other phenotypes never read. Set GenomeConfig::set_mutate_bias for CTRNNs.
+const IGNORED_GENOME_KEYS: &[&str] = &[
+    "activation_default", "activation_mutate_rate", "activation_options",
+    "aggregation_default", "aggregation_mutate_rate", "aggregation_options",
+    "bias_init_mean", "bias_init_stdev", "bias_init_type", "bias_max_value", "bias_min_value",
+    "bias_mutate_power", "bias_mutate_rate", "bias_replace_rate",
+    "response_init_mean", "response_init_stdev", "response_init_type", "response_max_value",
+    "response_min_value", "response_mutate_power", "response_mutate_rate", "response_replace_rate",
+    "conn_delete_prob", "node_delete_prob", "enabled_default",
+    "enabled_rate_to_true_add", "enabled_rate_to_false_add", "weight_init_type",
+    "weight_mutate_power",
+    "single_structural_mutation", "structural_mutation_surer",
+];
+
+#[derive(Debug)]
+pub enum IniError {
+    Io(io::Error),
+    Syntax { line: usize, message: String },
+    UnknownSection { line: usize, section: String },
+    UnknownKey { line: usize, section: String, key: String },
+    InvalidValue { line: usize, section: String, key: String, value: String, expected: String },
+    MissingKey { section: String, key: String },
+    Config(ConfigError),
+}
+
+impl fmt::Display for IniError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IniError::Io(error) => write!(f, "could not read config: {}", error),
+            IniError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            IniError::UnknownSection { line, section } => write!(f, "line {}: unknown section [{}]", line, section),
+            IniError::UnknownKey { line, section, key } => {
+                write!(f, "line {}: unknown key {:?} in [{}]", line, key, section)
+            },
+            IniError::InvalidValue { line, section, key, value, expected } => {
+                write!(f, "line {}: invalid value {:?} for {} in [{}], expected {}", line, value, key, section, expected)
+            },
+            IniError::MissingKey { section, key } => write!(f, "missing required key {} in [{}]", key, section),
+            IniError::Config(error) => write!(f, "invalid settings: {}", error),
+        }
+    }
+}
+
+impl Error for IniError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IniError::Io(error) => Some(error),
+            IniError::Config(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<ConfigError> for IniError {
+    fn from(error: ConfigError) -> IniError {
+        IniError::Config(error)
+    }
+}
+
+impl From<io::Error> for IniError {
+    fn from(error: io::Error) -> IniError {
+        IniError::Io(error)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IniConfig {
+    genome_config: GenomeConfig,
+    neat_config: NeatConfig,
+    fitness_criterion: FitnessCriterion,
+    fitness_threshold: f64,
+    no_fitness_termination: bool,
+}
+
+impl IniConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<IniConfig, IniError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn get_genome_config(&self) -> &GenomeConfig {
+        &self.genome_config
+    }
+
+    pub fn get_neat_config(&self) -> &NeatConfig {
+        &self.neat_config
+    }
+
+    pub fn get_fitness_criterion(&self) -> FitnessCriterion {
+        self.fitness_criterion
+    }
+
+    pub fn get_fitness_threshold(&self) -> f64 {
+        self.fitness_threshold
+    }
+
+    pub fn is_fitness_termination(&self) -> bool {
+        !self.no_fitness_termination
+    }
+
+    pub fn get_criteria(&self) -> Criteria {
+        let mut criteria = Criteria::new();
+        if !self.no_fitness_termination {
+            criteria.set_fitness_threshold(self.fitness_criterion, self.fitness_threshold);
+        }
+        criteria
+    }
+
+    pub fn into_neat(self) -> Neat {
+        Neat::with_config(self.neat_config, self.genome_config)
+    }
+}
+
+impl FromStr for IniConfig {
+    type Err = IniError;
+
+    fn from_str(s: &str) -> Result<IniConfig, IniError> {
+        let sections = parse_sections(s)?;
+
+        sections.check_keys(NEAT, &["pop_size", "fitness_criterion", "fitness_threshold", "reset_on_extinction", "no_fitness_termination"], &[])?;
+        sections.check_keys(GENOME, &[
+            "num_inputs", "num_outputs", "num_hidden", "initial_connection",
+            "compatibility_disjoint_coefficient", "compatibility_weight_coefficient",
+            "conn_add_prob", "node_add_prob", "weight_init_mean", "weight_init_stdev",
+            "weight_mutate_rate", "weight_replace_rate", "enabled_mutate_rate",
+            "weight_min_value", "weight_max_value", "feed_forward",
+        ], IGNORED_GENOME_KEYS)?;
+        sections.check_keys(REPRODUCTION, &["elitism", "survival_threshold", "min_species_size"], &[])?;
+        sections.check_keys(SPECIES_SET, &["compatibility_threshold"], &[])?;
+        sections.check_keys(STAGNATION, &["max_stagnation", "species_fitness_func", "species_elitism"], &[])?;
+
+        let mut genome_config = GenomeConfig::new(
+            sections.require(GENOME, "num_inputs", "an integer")?,
+            sections.require(GENOME, "num_outputs", "an integer")?
+        );
+
+        let num_hidden: u32 = sections.get(GENOME, "num_hidden", "an integer")?.unwrap_or(0);
+        if num_hidden != 0 {
+            return Err(sections.invalid(GENOME, "num_hidden", "0, initial hidden neurons are not supported"));
+        }
+
+        let initial_connection: String = sections.get(GENOME, "initial_connection", "a connection scheme")?
+            .unwrap_or_else(|| "unconnected".to_string());
+        match initial_connection.as_str() {
+            "unconnected" => genome_config.set_is_connected(false),
+            "full" | "full_direct" | "full_nodirect" => genome_config.set_is_connected(true),
+            _ => return Err(sections.invalid(GENOME, "initial_connection", "unconnected, full, full_direct or full_nodirect")),
+        }
+
+        if let Some(value) = sections.get(GENOME, "compatibility_disjoint_coefficient", "a number")? {
+            genome_config.set_compatibility_disjoint_coefficient(value);
+        }
+        if let Some(value) = sections.get(GENOME, "compatibility_weight_coefficient", "a number")? {
+            genome_config.set_compatibility_weight_coefficient(value);
+        }
+        if let Some(value) = sections.get(GENOME, "conn_add_prob", "a probability")? {
+            genome_config.set_mutate_create_connection(value);
+        }
+        if let Some(value) = sections.get(GENOME, "node_add_prob", "a probability")? {
+            genome_config.set_mutate_create_neuron(value);
+        }
+        if let Some(value) = sections.get(GENOME, "weight_init_mean", "a number")? {
+            genome_config.set_default_weight(value);
+        }
+        if let Some(value) = sections.get(GENOME, "weight_init_stdev", "a number")? {
+            genome_config.set_weight_deviation(value);
+        }
+        if let Some(value) = sections.get(GENOME, "weight_min_value", "a number")? {
+            genome_config.set_weight_min_value(value);
+        }
+        if let Some(value) = sections.get(GENOME, "weight_max_value", "a number")? {
+            genome_config.set_weight_max_value(value);
+        }
+        if let Some(value) = sections.get(GENOME, "weight_mutate_rate", "a probability")? {
+            genome_config.set_mutate_update_weight(value);
+        }
+        if let Some(value) = sections.get(GENOME, "weight_replace_rate", "a probability")? {
+            genome_config.set_mutate_set_weight(value);
+        }
+        if let Some(value) = sections.get(GENOME, "enabled_mutate_rate", "a probability")? {
+            genome_config.set_mutate_toggle_connection(value);
+        }
+        if let Some(value) = sections.get::<Bool>(GENOME, "feed_forward", "a boolean")? {
+            genome_config.set_feed_forward(value.0);
+        }
+        genome_config.validate()?;
+        for &key in &["enabled_default", "single_structural_mutation"] {
+            sections.get::<Bool>(GENOME, key, "a boolean")?;
+        }
+
+        let mut neat_config = NeatConfig::new(sections.require(NEAT, "pop_size", "an integer")?);
+        neat_config.set_compatibility_threshold(sections.require(SPECIES_SET, "compatibility_threshold", "a number")?);
+        neat_config.set_elitism(sections.get(REPRODUCTION, "elitism", "an integer")?.unwrap_or(0));
+        neat_config.set_survival_threshold(sections.get(REPRODUCTION, "survival_threshold", "a number")?.unwrap_or(0.2));
+        neat_config.set_max_stagnation(sections.get(STAGNATION, "max_stagnation", "an integer")?.unwrap_or(15));
+        neat_config.validate()?;
+        sections.get::<usize>(REPRODUCTION, "min_species_size", "an integer")?;
+        sections.get::<usize>(STAGNATION, "species_elitism", "an integer")?;
+        let species_fitness_func: Option<String> = sections.get(STAGNATION, "species_fitness_func", "a function name")?;
+        if let Some(function) = species_fitness_func {
+            if !["max", "min", "mean", "median"].contains(&function.as_str()) {
+                return Err(sections.invalid(STAGNATION, "species_fitness_func", "max, min, mean or median"));
+            }
+        }
+
+        let fitness_criterion: String = sections.get(NEAT, "fitness_criterion", "max or mean")?
+            .unwrap_or_else(|| "max".to_string());
+        let fitness_criterion = match fitness_criterion.as_str() {
+            "max" => FitnessCriterion::Max,
+            "mean" => FitnessCriterion::Mean,
+            _ => return Err(sections.invalid(NEAT, "fitness_criterion", "max or mean")),
+        };
+        let no_fitness_termination = sections.get::<Bool>(NEAT, "no_fitness_termination", "a boolean")?
+            .is_some_and(|value| value.0);
+        sections.get::<Bool>(NEAT, "reset_on_extinction", "a boolean")?;
+        let fitness_threshold = if no_fitness_termination {
+            sections.get(NEAT, "fitness_threshold", "a number")?.unwrap_or(f64::INFINITY)
+        } else {
+            sections.require(NEAT, "fitness_threshold", "a number")?
+        };
+
+        Ok(IniConfig {
+            genome_config,
+            neat_config,
+            fitness_criterion,
+            fitness_threshold,
+            no_fitness_termination,
+        })
+    }
+}
+
+struct Bool(bool);
+
+impl FromStr for Bool {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Bool, ()> {
+        match s.to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(Bool(true)),
+            "false" | "no" | "off" | "0" => Ok(Bool(false)),
+            _ => Err(())
+        }
+    }
+}
+
+struct Entry {
+    line: usize,
+    value: String,
+}
+
+struct Sections {
+    sections: HashMap<String, (usize, HashMap<String, Entry>)>,
+}
+
+fn parse_sections(s: &str) -> Result<Sections, IniError> {
+    let mut sections: HashMap<String, (usize, HashMap<String, Entry>)> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for (index, raw_line) in s.lines().enumerate() {
+        let line = index + 1;
+        let content = raw_line.trim();
+        if content.is_empty() || content.starts_with('#') || content.starts_with(';') {
+            continue;
+        }
+
+        if content.starts_with('[') {
+            let section = content.strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+                .ok_or_else(|| IniError::Syntax { line, message: "unterminated section header".to_string() })?
+                .trim()
+                .to_string();
+            if ![NEAT, GENOME, REPRODUCTION, SPECIES_SET, STAGNATION].contains(&section.as_str()) {
+                return Err(IniError::UnknownSection { line, section });
+            }
+            sections.entry(section.clone()).or_insert_with(|| (line, HashMap::new()));
+            current = Some(section);
+            continue;
+        }
+
+        let split = content.find(['=', ':'])
+            .ok_or_else(|| IniError::Syntax { line, message: format!("expected key = value, found {:?}", content) })?;
+        let key = content[..split].trim().to_lowercase();
+        let value = content[split + 1..].trim().to_string();
+        let section = current.as_ref()
+            .ok_or_else(|| IniError::Syntax { line, message: format!("key {:?} outside of a section", key) })?;
+
+        let entries = &mut sections.get_mut(section).unwrap().1;
+        if entries.insert(key.clone(), Entry { line, value }).is_some() {
+            return Err(IniError::Syntax { line, message: format!("duplicate key {:?} in [{}]", key, section) });
+        }
+    }
+
+    Ok(Sections { sections })
+}
+
+impl Sections {
+    fn check_keys(&self, section: &str, known: &[&str], ignored: &[&str]) -> Result<(), IniError> {
+        if let Some((_, entries)) = self.sections.get(section) {
+            for (key, entry) in entries.iter() {
+                if !known.contains(&key.as_str()) && !ignored.contains(&key.as_str()) {
+                    return Err(IniError::UnknownKey {
+                        line: entry.line,
+                        section: section.to_string(),
+                        key: key.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn get<T: FromStr>(&self, section: &str, key: &str, expected: &str) -> Result<Option<T>, IniError> {
+        match self.sections.get(section).and_then(|(_, entries)| entries.get(key)) {
+            Some(entry) => entry.value.parse::<T>()
+                .map(Some)
+                .map_err(|_| self.invalid(section, key, expected)),
+            None => Ok(None)
+        }
+    }
+
+    fn require<T: FromStr>(&self, section: &str, key: &str, expected: &str) -> Result<T, IniError> {
+        self.get(section, key, expected)?.ok_or_else(|| IniError::MissingKey {
+            section: section.to_string(),
+            key: key.to_string(),
+        })
+    }
+
+    fn invalid(&self, section: &str, key: &str, expected: &str) -> IniError {
+        let entry = self.sections.get(section).and_then(|(_, entries)| entries.get(key));
+        IniError::InvalidValue {
+            line: entry.map_or(0, |entry| entry.line),
+            section: section.to_string(),
+            key: key.to_string(),
+            value: entry.map_or(String::new(), |entry| entry.value.clone()),
+            expected: expected.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XOR_CONFIG: &str = "
+#--- parameters for the XOR-2 experiment ---#
+
+[NEAT]
+fitness_criterion     = max
+fitness_threshold     = 3.9
+pop_size              = 150
+reset_on_extinction   = False
+
+[DefaultGenome]
+# node activation options
+activation_default      = sigmoid
+activation_mutate_rate  = 0.0
+activation_options      = sigmoid
+
+# node bias options
+bias_init_mean          = 0.0
+bias_init_stdev         = 1.0
+bias_max_value          = 30.0
+bias_min_value          = -30.0
+bias_mutate_power       = 0.5
+bias_mutate_rate        = 0.7
+bias_replace_rate       = 0.1
+
+# genome compatibility options
+compatibility_disjoint_coefficient = 1.0
+compatibility_weight_coefficient   = 0.5
+
+# connection add/remove rates
+conn_add_prob           = 0.5
+conn_delete_prob        = 0.5
+
+# connection enable options
+enabled_default         = True
+enabled_mutate_rate     = 0.01
+
+feed_forward            = True
+initial_connection      = full
+
+# node add/remove rates
+node_add_prob           = 0.2
+node_delete_prob        = 0.2
+
+# network parameters
+num_hidden              = 0
+num_inputs              = 2
+num_outputs             = 1
+
+# connection weight options
+weight_init_mean        = 0.0
+weight_init_stdev       = 1.0
+weight_max_value        = 30
+weight_min_value        = -30
+weight_mutate_power     = 0.5
+weight_mutate_rate      = 0.8
+weight_replace_rate     = 0.1
+
+[DefaultSpeciesSet]
+compatibility_threshold = 3.0
+
+[DefaultStagnation]
+species_fitness_func = max
+max_stagnation       = 20
+
+[DefaultReproduction]
+elitism            = 2
+survival_threshold = 0.2
+";
+
+    #[test]
+    fn test_load_xor_config() {
+        let config: IniConfig = XOR_CONFIG.parse().unwrap();
+
+        let genome_config = config.get_genome_config();
+        assert_eq!(genome_config.get_n_sensor(), 2);
+        assert_eq!(genome_config.get_n_output(), 1);
+        assert!(genome_config.is_connected());
+        assert_eq!(genome_config.get_mutate_create_connection(), 0.5);
+        assert_eq!(genome_config.get_mutate_create_neuron(), 0.2);
+        assert_eq!(genome_config.get_mutate_update_weight(), 0.8);
+        assert_eq!(genome_config.get_mutate_toggle_connection(), 0.01);
+        assert_eq!(genome_config.get_weight_max_value(), 30.0);
+        // bias_mutate_rate is 0.7 in the file, but the bias only affects CtrnnNetwork.
+        assert_eq!(genome_config.get_mutate_bias(), 0.0);
+
+        let neat_config = config.get_neat_config();
+        assert_eq!(neat_config.get_population_size(), 150);
+        assert_eq!(neat_config.get_elitism(), 2);
+        assert_eq!(neat_config.get_max_stagnation(), 20);
+        assert_eq!(config.get_fitness_threshold(), 3.9);
+        assert_eq!(config.get_fitness_criterion(), FitnessCriterion::Max);
+
+        assert_eq!(config.into_neat().iter_population().count(), 150);
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config: IniConfig = "
+[NEAT]
+pop_size = 10
+fitness_threshold = 1
+
+[DefaultGenome]
+num_inputs = 3
+num_outputs = 2
+
+[DefaultSpeciesSet]
+compatibility_threshold = 2.5
+".parse().unwrap();
+
+        assert!(!config.get_genome_config().is_connected());
+        assert_eq!(config.get_neat_config().get_elitism(), 0);
+        assert_eq!(config.get_neat_config().get_survival_threshold(), 0.2);
+        assert_eq!(config.get_neat_config().get_max_stagnation(), 15);
+        assert_eq!(config.get_neat_config().get_compatibility_threshold(), 2.5);
+    }
+
+    #[test]
+    fn test_errors() {
+        let with = |extra: &str| format!("{}\n{}", XOR_CONFIG, extra).parse::<IniConfig>().unwrap_err();
+
+        match with("[DefaultSpeciesSet]\ncompatibility_threshold = 3") {
+            IniError::Syntax { message, .. } => assert!(message.contains("duplicate")),
+            error => panic!("unexpected error {}", error),
+        }
+        match XOR_CONFIG.replace("max_stagnation       = 20", "max_stagnation = twenty").parse::<IniConfig>().unwrap_err() {
+            IniError::InvalidValue { key, value, .. } => {
+                assert_eq!(key, "max_stagnation");
+                assert_eq!(value, "twenty");
+            },
+            error => panic!("unexpected error {}", error),
+        }
+        match XOR_CONFIG.replace("weight_mutate_rate", "weight_mutation_rate").parse::<IniConfig>().unwrap_err() {
+            IniError::UnknownKey { key, section, .. } => {
+                assert_eq!(key, "weight_mutation_rate");
+                assert_eq!(section, "DefaultGenome");
+            },
+            error => panic!("unexpected error {}", error),
+        }
+        match with("[DefaultSpeciesSett]") {
+            IniError::UnknownSection { section, .. } => assert_eq!(section, "DefaultSpeciesSett"),
+            error => panic!("unexpected error {}", error),
+        }
+        match XOR_CONFIG.replace("pop_size              = 150", "").parse::<IniConfig>().unwrap_err() {
+            IniError::MissingKey { key, .. } => assert_eq!(key, "pop_size"),
+            error => panic!("unexpected error {}", error),
+        }
+        assert!(XOR_CONFIG.replace("num_hidden              = 0", "num_hidden = 2").parse::<IniConfig>().is_err());
+        match XOR_CONFIG.replace("conn_add_prob           = 0.5", "conn_add_prob = 1.5").parse::<IniConfig>().unwrap_err() {
+            IniError::Config(ConfigError::Probability { name, .. }) => assert_eq!(name, "mutate_create_connection"),
+            error => panic!("unexpected error {}", error),
+        }
+        match XOR_CONFIG.replace("pop_size              = 150", "pop_size = 0").parse::<IniConfig>().unwrap_err() {
+            IniError::Config(ConfigError::OutOfRange { name, .. }) => assert_eq!(name, "population_size"),
+            error => panic!("unexpected error {}", error),
+        }
+        match XOR_CONFIG.replace("compatibility_threshold = 3.0", "compatibility_threshold = nan").parse::<IniConfig>().unwrap_err() {
+            IniError::Config(ConfigError::OutOfRange { name, .. }) => assert_eq!(name, "compatibility_threshold"),
+            error => panic!("unexpected error {}", error),
+        }
+        match XOR_CONFIG.replace("survival_threshold = 0.2", "survival_threshold = 1.5").parse::<IniConfig>().unwrap_err() {
+            IniError::Config(ConfigError::Probability { name, .. }) => assert_eq!(name, "survival_threshold"),
+            error => panic!("unexpected error {}", error),
+        }
+    }
+}
@@ -1,22 +1,430 @@
-#![feature(is_sorted)]
 #![allow(dead_code)]
 
 mod utils;
-mod genome;
+pub mod error;
+pub mod genome;
+pub mod evaluator;
+pub mod neat_config;
+pub mod species;
+pub mod run;
+pub mod reporter;
+pub mod statistics;
+pub mod checkpoint;
+pub mod ini;
+pub mod render;
+pub mod network;
 
+extern crate rand;
+
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::rc::Rc;
-use std::cell::RefCell;
-use genome::Genome;
+use rand::{ thread_rng, Rng, SeedableRng, seq::SliceRandom };
+use utils::NeatRng;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub use error::NeatError;
+pub use genome::{ Genome, Gene };
+pub use genome::validate::Violation;
+pub use genome::genome_config::{ GenomeConfig, GenomeConfigBuilder, ConfigError };
+pub use evaluator::{ FitnessEvaluator, ExtraData };
+#[cfg(feature = "parallel")]
+pub use evaluator::ParallelEvaluator;
+pub use neat_config::NeatConfig;
+pub use species::Species;
+pub use run::{ Criteria, FitnessCriterion, RunResult, StopReason };
+pub use reporter::{ Reporter, StdoutReporter };
+pub use statistics::{ GenerationStatistics, StatisticsReporter };
+pub use checkpoint::Checkpointer;
+pub use ini::{ IniConfig, IniError };
+pub use render::dot::DotOptions;
+pub use render::svg::SvgOptions;
+pub use network::feed_forward::FeedForwardNetwork;
+pub use network::recurrent::RecurrentNetwork;
+pub use network::ctrnn::{ CtrnnNetwork, Integration };
+pub use network::c::CSource;
+pub use network::quantized::{ Precision, QuantizedNetwork };
+pub use network::compiled::{ CompiledNetwork, Scalar };
 
 pub struct Neat {
-    counter: Rc<RefCell<InnovationCounter>>,
-    population_genome: Vec<Genome>
+    counter: InnovationCounter,
+    config: GenomeConfig,
+    neat_config: NeatConfig,
+    population_genome: Vec<Genome>,
+    fitness: Vec<f64>,
+    extra_data: Vec<ExtraData>,
+    species: Vec<Species>,
+    next_species_id: usize,
+    generation: usize,
+    champion: Option<(Genome, f64, usize)>,
+    rng: NeatRng,
+    reporters: Vec<Box<dyn Reporter>>,
+    checkpointer: Option<Checkpointer>,
+}
+
+impl Neat {
+    pub fn new(population_size: usize, config: GenomeConfig) -> Neat {
+        Neat::with_config(NeatConfig::new(population_size), config)
+    }
+
+    pub fn with_config(neat_config: NeatConfig, config: GenomeConfig) -> Neat {
+        Neat::with_seed(neat_config, config, thread_rng().gen())
+    }
+
+    pub fn with_seed(neat_config: NeatConfig, config: GenomeConfig, seed: u64) -> Neat {
+        let mut rng = NeatRng::seed_from_u64(seed);
+        let mut counter = InnovationCounter::new(config.get_n_sensor() + config.get_n_output());
+        let population_genome = (0..neat_config.get_population_size())
+            .map(|_| Genome::new_with_rng(&mut counter, &config, &mut rng))
+            .collect::<Result<_, _>>()
+            // A fresh counter hands out one innovation per neuron and sensor-output
+            // pair, far fewer than u32::MAX for any population that fits in memory.
+            .expect("a fresh innovation counter cannot overflow");
+
+        Neat::from_population(neat_config, config, counter, population_genome, rng)
+    }
+
+    // The counter starts from the seed alone, so its connections keep their own innovation numbers.
+    pub fn with_genome(neat_config: NeatConfig, config: GenomeConfig, genome: &Genome) -> Result<Neat, NeatError> {
+        genome.validate_with(&config).map_err(NeatError::InvalidGenome)?;
+
+        let mut counter = InnovationCounter::new(config.get_n_sensor() + config.get_n_output());
+        counter.register_genome(genome)?;
+        let population_genome = vec![genome.clone(); neat_config.get_population_size()];
+        let rng = NeatRng::seed_from_u64(thread_rng().gen());
+
+        Ok(Neat::from_population(neat_config, config, counter, population_genome, rng))
+    }
+
+    fn from_population(
+        neat_config: NeatConfig,
+        config: GenomeConfig,
+        counter: InnovationCounter,
+        population_genome: Vec<Genome>,
+        rng: NeatRng
+    ) -> Neat {
+        Neat {
+            counter,
+            config,
+            neat_config,
+            population_genome,
+            fitness: Vec::new(),
+            extra_data: Vec::new(),
+            species: Vec::new(),
+            next_species_id: 0,
+            generation: 0,
+            champion: None,
+            rng,
+            reporters: Vec::new(),
+            checkpointer: None,
+        }
+    }
+
+    pub fn add_reporter<R>(&mut self, reporter: R)
+    where
+        R: Reporter + 'static
+    {
+        self.reporters.push(Box::new(reporter));
+    }
+
+    fn report<F>(&mut self, mut callback: F)
+    where
+        F: FnMut(&mut dyn Reporter, &Neat)
+    {
+        let mut reporters = std::mem::take(&mut self.reporters);
+        for reporter in reporters.iter_mut() {
+            callback(reporter.as_mut(), self);
+        }
+        self.reporters = reporters;
+    }
+
+    pub fn evaluate_population<E>(&mut self, evaluator: &mut E) -> Result<(), NeatError>
+    where
+        E: FitnessEvaluator + ?Sized
+    {
+        let generation = self.generation;
+        self.report(|reporter, _| reporter.start_generation(generation));
+
+        let fitness = evaluator.evaluate_population(&self.population_genome);
+        if fitness.len() != self.population_genome.len() {
+            return Err(NeatError::FitnessCount { expected: self.population_genome.len(), found: fitness.len() });
+        }
+        self.fitness = fitness;
+        self.extra_data = evaluator.take_extra_data();
+        self.speciate();
+        self.update_champion();
+
+        self.report(|reporter, neat| reporter.post_evaluate(neat));
+        Ok(())
+    }
+
+    // Index and fitness of the best genome of the current generation; ties keep the first.
+    fn find_best(&self) -> Option<(usize, f64)> {
+        self.fitness.iter()
+            .enumerate()
+            .fold(None, |best: Option<(usize, f64)>, (index, &fitness)| match best {
+                Some((_, best_fitness)) if best_fitness >= fitness => best,
+                _ => Some((index, fitness))
+            })
+    }
+
+    fn update_champion(&mut self) {
+        if let Some((index, fitness)) = self.find_best() {
+            let improved = match self.champion {
+                Some((_, champion_fitness, _)) => fitness > champion_fitness,
+                None => true
+            };
+            if improved {
+                self.champion = Some((self.population_genome[index].clone(), fitness, self.generation));
+                self.report(|reporter, neat| {
+                    if let Some((genome, fitness, generation)) = neat.champion.as_ref() {
+                        reporter.new_best(genome, *fitness, *generation);
+                    }
+                });
+            }
+        }
+    }
+
+    fn end_generation(&mut self) {
+        self.report(|reporter, neat| reporter.end_generation(neat));
+    }
+
+    pub fn next_generation<E>(&mut self, evaluator: &mut E) -> Result<(), NeatError>
+    where
+        E: FitnessEvaluator + ?Sized
+    {
+        self.evaluate_population(evaluator)?;
+        self.reproduce()
+    }
+
+    pub fn reproduce(&mut self) -> Result<(), NeatError> {
+        if self.fitness.len() != self.population_genome.len() {
+            self.fitness.resize(self.population_genome.len(), 0.0);
+            self.speciate();
+        }
+        self.end_generation();
+        self.remove_stagnant_species();
+
+        let spawn = self.compute_spawn();
+        let rng = &mut self.rng;
+        let fitness = &self.fitness;
+        let mut new_population = Vec::with_capacity(self.neat_config.get_population_size());
+
+        for (species, &n_spawn) in self.species.iter().zip(spawn.iter()) {
+            let mut members = species.get_members().to_vec();
+            members.sort_by(|&a, &b| fitness[b].partial_cmp(&fitness[a]).unwrap_or(Ordering::Equal));
+
+            let elites = self.neat_config.get_elitism().min(n_spawn);
+            for &index in members.iter().take(elites) {
+                new_population.push(self.population_genome[index].clone());
+            }
+
+            let survivors = (members.len() as f64 * self.neat_config.get_survival_threshold()).ceil() as usize;
+            let parents = &members[..survivors.max(1).min(members.len())];
+
+            for _ in elites..n_spawn {
+                let (parent1, parent2) = match (parents.choose(rng), parents.choose(rng)) {
+                    (Some(&parent1), Some(&parent2)) => (parent1, parent2),
+                    _ => break
+                };
+                let (fitter, other) = if fitness[parent1] >= fitness[parent2] {
+                    (parent1, parent2)
+                } else {
+                    (parent2, parent1)
+                };
+
+                let mut child = Genome::crossover_with_rng(
+                    &self.population_genome[fitter],
+                    &self.population_genome[other],
+                    &self.config,
+                    rng
+                )?;
+                child.mutate_with_rng(&mut self.counter, &self.config, rng)?;
+                new_population.push(child);
+            }
+        }
+
+        self.population_genome = new_population;
+        self.fitness.clear();
+        self.extra_data.clear();
+        self.generation += 1;
+
+        // The new generation is complete even when saving it fails.
+        self.checkpoint()?;
+        Ok(())
+    }
+
+    fn speciate(&mut self) {
+        let threshold = self.neat_config.get_compatibility_threshold();
+
+        for species in self.species.iter_mut() {
+            species.clear_members();
+        }
+
+        let mut created = Vec::new();
+        for (index, genome) in self.population_genome.iter().enumerate() {
+            let config = &self.config;
+            let found = self.species.iter_mut()
+                .find(|species| species.get_representative().distance(genome, config) < threshold);
+
+            match found {
+                Some(species) => species.add_member(index),
+                None => {
+                    let mut species = Species::new(self.next_species_id, genome.clone(), self.generation);
+                    species.add_member(index);
+                    self.species.push(species);
+                    created.push(self.next_species_id);
+                    self.next_species_id += 1;
+                }
+            }
+        }
+
+        let (extinct, alive): (Vec<Species>, Vec<Species>) = std::mem::take(&mut self.species)
+            .into_iter()
+            .partition(|species| species.get_members().is_empty());
+        self.species = alive;
+        self.report_species_changes(&created, &extinct);
+
+        let rng = &mut self.rng;
+        let (fitness, population) = (&self.fitness, &self.population_genome);
+        for species in self.species.iter_mut() {
+            let best = species.get_members().iter()
+                .map(|&index| fitness[index])
+                .fold(f64::NEG_INFINITY, f64::max);
+            species.update_fitness(best, self.generation);
+
+            if let Some(&representative) = species.get_members().choose(rng) {
+                species.set_representative(population[representative].clone());
+            }
+        }
+    }
+
+    fn remove_stagnant_species(&mut self) {
+        let best_species = self.species.iter()
+            .max_by(|a, b| a.get_best_fitness().partial_cmp(&b.get_best_fitness()).unwrap_or(Ordering::Equal))
+            .map(|species| species.get_id());
+        let (generation, max_stagnation) = (self.generation, self.neat_config.get_max_stagnation());
+
+        let (alive, extinct): (Vec<Species>, Vec<Species>) = std::mem::take(&mut self.species)
+            .into_iter()
+            .partition(|species| {
+                Some(species.get_id()) == best_species || !species.is_stagnant(generation, max_stagnation)
+            });
+        self.species = alive;
+        self.report_species_changes(&[], &extinct);
+    }
+
+    fn report_species_changes(&mut self, created: &[usize], extinct: &[Species]) {
+        let generation = self.generation;
+        self.report(|reporter, neat| {
+            for species in neat.species.iter().filter(|species| created.contains(&species.get_id())) {
+                reporter.species_created(species, generation);
+            }
+            for species in extinct {
+                reporter.species_extinct(species, generation);
+            }
+        });
+    }
+
+    fn compute_spawn(&self) -> Vec<usize> {
+        let population_size = self.neat_config.get_population_size();
+        if self.species.is_empty() {
+            return Vec::new();
+        }
+
+        let min_fitness = self.fitness.iter().cloned().fold(f64::INFINITY, f64::min);
+        let adjusted: Vec<f64> = self.species.iter()
+            .map(|species| {
+                let members = species.get_members();
+                members.iter().map(|&index| self.fitness[index] - min_fitness).sum::<f64>() / members.len() as f64
+            })
+            .collect();
+        let total: f64 = adjusted.iter().sum();
+
+        let mut spawn: Vec<usize> = adjusted.iter()
+            .map(|&value| {
+                if total > 0.0 {
+                    (value / total * population_size as f64).floor() as usize
+                } else {
+                    population_size / self.species.len()
+                }
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..spawn.len()).collect();
+        order.sort_by(|&a, &b| adjusted[b].partial_cmp(&adjusted[a]).unwrap_or(Ordering::Equal));
+
+        let mut remaining = population_size - spawn.iter().sum::<usize>();
+        for &index in order.iter().cycle() {
+            if remaining == 0 {
+                break;
+            }
+            spawn[index] += 1;
+            remaining -= 1;
+        }
+
+        spawn
+    }
+
+    pub fn evaluate<F>(&self, fitness: F) -> Vec<f64>
+    where
+        F: Fn(&Genome) -> f64
+    {
+        self.population_genome.iter().map(fitness).collect()
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn par_evaluate<F>(&self, fitness: F) -> Vec<f64>
+    where
+        F: Fn(&Genome) -> f64 + Sync
+    {
+        self.population_genome.par_iter().map(&fitness).collect()
+    }
+
+    pub fn get_config(&self) -> &GenomeConfig {
+        &self.config
+    }
+
+    pub fn get_neat_config(&self) -> &NeatConfig {
+        &self.neat_config
+    }
+
+    pub fn get_fitness(&self) -> &[f64] {
+        &self.fitness
+    }
+
+    pub fn get_extra_data(&self) -> &[ExtraData] {
+        &self.extra_data
+    }
+
+    pub fn get_species(&self) -> &[Species] {
+        &self.species
+    }
+
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn get_champion(&self) -> Option<(&Genome, f64, usize)> {
+        self.champion.as_ref().map(|(genome, fitness, generation)| (genome, *fitness, *generation))
+    }
+
+    pub fn get_counter(&self) -> &InnovationCounter {
+        &self.counter
+    }
+
+    pub fn iter_population(&self) -> std::slice::Iter<'_, Genome> {
+        self.population_genome.iter()
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct InnovationCounter {
     counter: u32,
+    #[cfg_attr(feature = "serde", serde(with = "utils::serde_innovation_map"))]
     connections_innovation_map: HashMap<(u32, u32), u32>
 }
 
@@ -28,22 +436,43 @@ impl InnovationCounter {
         }
     }
 
-    pub fn get_connection_innovation(&mut self, neuron_in: u32, neuron_out: u32) -> u32 {
+    pub fn get_connection_innovation(&mut self, neuron_in: u32, neuron_out: u32) -> Result<u32, NeatError> {
         match self.connections_innovation_map.get(&(neuron_in, neuron_out)) {
-            Some(&innovation) => innovation,
+            Some(&innovation) => Ok(innovation),
             None => {
-                let innovation = self.counter;
-                self.connections_innovation_map.insert((neuron_in, neuron_out), self.counter);
-                self.counter += 1;
-                innovation
+                let innovation = self.next_innovation()?;
+                self.connections_innovation_map.insert((neuron_in, neuron_out), innovation);
+                Ok(innovation)
             }
         }
     }
 
-    pub fn get_neuron_innovation(&mut self) -> u32 {
+    pub fn register_genome(&mut self, genome: &Genome) -> Result<(), NeatError> {
+        let next = |innovation: u32| innovation.checked_add(1).ok_or(NeatError::InnovationOverflow);
+        for neuron in genome.iter_neurons() {
+            self.counter = self.counter.max(next(neuron.get_innovation_number())?);
+        }
+        for connection in genome.iter_connections() {
+            self.counter = self.counter.max(next(connection.get_innovation_number())?);
+            self.connections_innovation_map
+                .entry((connection.get_neuron_in(), connection.get_neuron_out()))
+                .or_insert_with(|| connection.get_innovation_number());
+        }
+        Ok(())
+    }
+
+    pub fn get_innovation_count(&self) -> u32 {
+        self.counter
+    }
+
+    pub fn get_neuron_innovation(&mut self) -> Result<u32, NeatError> {
+        self.next_innovation()
+    }
+
+    fn next_innovation(&mut self) -> Result<u32, NeatError> {
         let innovation = self.counter;
-        self.counter += 1;
-        innovation
+        self.counter = innovation.checked_add(1).ok_or(NeatError::InnovationOverflow)?;
+        Ok(innovation)
     }
 }
 
@@ -55,17 +484,119 @@ mod tests {
     fn counter_test() {
         let mut counter = InnovationCounter::new(0);
 
-        let conn1 = counter.get_connection_innovation(1, 2);
-        let conn2 = counter.get_connection_innovation(1, 2);
-        let conn3 = counter.get_connection_innovation(2, 3);
+        let conn1 = counter.get_connection_innovation(1, 2).unwrap();
+        let conn2 = counter.get_connection_innovation(1, 2).unwrap();
+        let conn3 = counter.get_connection_innovation(2, 3).unwrap();
 
         assert_eq!(conn1, conn2);
 
         assert!(conn1 < conn3);
 
-        let neur1 = counter.get_neuron_innovation();
-        let neur2 = counter.get_neuron_innovation();
+        let neur1 = counter.get_neuron_innovation().unwrap();
+        let neur2 = counter.get_neuron_innovation().unwrap();
 
         assert!(neur1 < neur2);
     }
+
+    #[test]
+    fn neat_evaluate_test() {
+        let mut config = GenomeConfig::new(3, 2);
+        config.set_is_connected(true);
+        let neat = Neat::new(10, config);
+
+        let fitness = |genome: &Genome| {
+            genome.iter_connections().map(|connection| connection.get_weight()).sum::<f64>()
+        };
+        let scores = neat.evaluate(fitness);
+
+        assert_eq!(scores.len(), 10);
+        for (score, genome) in scores.iter().zip(neat.iter_population()) {
+            assert_eq!(*score, fitness(genome));
+        }
+
+        #[cfg(feature = "parallel")]
+        assert_eq!(neat.par_evaluate(fitness), scores);
+    }
+
+    #[test]
+    fn neat_with_genome_test() {
+        let seed: Genome = "neuron 0 sensor\nneuron 1 output\nneuron 7 hidden 100\n\
+                            connection 8 0 7 1 enabled\nconnection 9 7 1 1 enabled".parse().unwrap();
+        let mut neat = Neat::with_genome(NeatConfig::new(5), GenomeConfig::new(1, 1), &seed).unwrap();
+
+        assert!(neat.iter_population().all(|genome| genome.to_string() == seed.to_string()));
+        assert_eq!(neat.counter.get_connection_innovation(0, 7).unwrap(), 8);
+        assert_eq!(neat.counter.get_neuron_innovation().unwrap(), 10);
+
+        // A connected config must not shadow the seed's own innovation for the direct link.
+        let direct: Genome = "neuron 0 sensor\nneuron 1 output\nconnection 5 0 1 1 enabled".parse().unwrap();
+        let mut config = GenomeConfig::new(1, 1);
+        config.set_is_connected(true);
+        let mut neat = Neat::with_genome(NeatConfig::new(5), config, &direct).unwrap();
+        assert_eq!(neat.counter.get_connection_innovation(0, 1).unwrap(), 5);
+
+        assert!(matches!(
+            Neat::with_genome(NeatConfig::new(5), GenomeConfig::new(2, 1), &seed),
+            Err(NeatError::InvalidGenome(_))
+        ));
+
+        let last: Genome = format!("neuron 0 sensor\nneuron {} output", u32::MAX).parse().unwrap();
+        assert!(matches!(
+            Neat::with_genome(NeatConfig::new(5), GenomeConfig::new(1, 1), &last),
+            Err(NeatError::InnovationOverflow)
+        ));
+        // The seed fits, but the next innovation would not.
+        let near: Genome = format!("neuron 0 sensor\nneuron {} output", u32::MAX - 1).parse().unwrap();
+        let mut config = GenomeConfig::new(1, 1);
+        config.set_mutate_create_connection(1.0);
+        let mut neat = Neat::with_genome(NeatConfig::new(1), config.clone(), &near).unwrap();
+        assert!(matches!(neat.counter.get_neuron_innovation(), Err(NeatError::InnovationOverflow)));
+        let mut genome = near.clone();
+        assert!(matches!(
+            genome.mutate_with_rng(&mut neat.counter, &config, &mut thread_rng()),
+            Err(NeatError::InnovationOverflow)
+        ));
+        assert_eq!(genome.to_string(), near.to_string());
+    }
+
+    #[test]
+    fn neat_next_generation_test() {
+        let mut config = GenomeConfig::new(3, 2);
+        config.set_is_connected(true);
+        config.set_mutate_create_neuron(0.5);
+        config.set_mutate_create_connection(0.5);
+        let mut neat = Neat::new(30, config);
+
+        let mut evaluator = |genome: &Genome| {
+            genome.iter_connections().filter(|connection| connection.is_enabled()).count() as f64
+        };
+
+        for generation in 0..20 {
+            assert_eq!(neat.get_generation(), generation);
+            neat.evaluate_population(&mut evaluator).unwrap();
+            assert_eq!(neat.get_fitness().len(), 30);
+            neat.reproduce().unwrap();
+
+            assert_eq!(neat.iter_population().count(), 30);
+            assert!(!neat.get_species().is_empty());
+        }
+
+        neat.next_generation(&mut evaluator).unwrap();
+        assert_eq!(neat.get_generation(), 21);
+
+        struct Short;
+        impl FitnessEvaluator for Short {
+            fn evaluate(&mut self, _: &Genome) -> f64 {
+                0.0
+            }
+
+            fn evaluate_population(&mut self, genomes: &[Genome]) -> Vec<f64> {
+                vec![0.0; genomes.len() - 1]
+            }
+        }
+        assert!(matches!(
+            neat.evaluate_population(&mut Short),
+            Err(NeatError::FitnessCount { expected: 30, found: 29 })
+        ));
+    }
 }
@@ -0,0 +1,76 @@
+use crate::genome::genome_config::ConfigError;
+
+#[derive(Clone, Debug)]
+pub struct NeatConfig {
+    population_size: usize,
+    compatibility_threshold: f64,
+    survival_threshold: f64,
+    elitism: usize,
+    max_stagnation: usize,
+}
+
+impl NeatConfig {
+    pub fn new(population_size: usize) -> NeatConfig {
+        NeatConfig {
+            population_size,
+            compatibility_threshold: 3.0,
+            survival_threshold: 0.2,
+            elitism: 1,
+            max_stagnation: 15,
+        }
+    }
+
+    pub fn set_population_size(&mut self, population_size: usize) {
+        self.population_size = population_size;
+    }
+
+    pub fn set_compatibility_threshold(&mut self, value: f64) {
+        self.compatibility_threshold = value;
+    }
+
+    pub fn set_survival_threshold(&mut self, value: f64) {
+        self.survival_threshold = value;
+    }
+
+    pub fn set_elitism(&mut self, elitism: usize) {
+        self.elitism = elitism;
+    }
+
+    pub fn set_max_stagnation(&mut self, max_stagnation: usize) {
+        self.max_stagnation = max_stagnation;
+    }
+
+    pub fn get_population_size(&self) -> usize {
+        self.population_size
+    }
+
+    pub fn get_compatibility_threshold(&self) -> f64 {
+        self.compatibility_threshold
+    }
+
+    pub fn get_survival_threshold(&self) -> f64 {
+        self.survival_threshold
+    }
+
+    pub fn get_elitism(&self) -> usize {
+        self.elitism
+    }
+
+    pub fn get_max_stagnation(&self) -> usize {
+        self.max_stagnation
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.population_size == 0 {
+            return Err(ConfigError::OutOfRange { name: "population_size", value: 0.0, expected: "at least 1" });
+        }
+        if !(0.0..=1.0).contains(&self.survival_threshold) {
+            return Err(ConfigError::Probability { name: "survival_threshold", value: self.survival_threshold });
+        }
+        let threshold = self.compatibility_threshold;
+        if !(threshold >= 0.0 && threshold.is_finite()) {
+            return Err(ConfigError::OutOfRange { name: "compatibility_threshold", value: threshold, expected: "a finite value >= 0" });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use super::{ Csr, Topology, check_identifier };
+use super::feed_forward::FeedForwardNetwork;
+use super::recurrent::RecurrentNetwork;
+use crate::NeatError;
+
+const C_KEYWORDS: &[&str] = &[
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum",
+    "extern", "float", "for", "goto", "if", "inline", "int", "long", "register", "restrict", "return",
+    "short", "signed", "sizeof", "static", "struct", "switch", "typedef", "union", "unsigned", "void",
+    "volatile", "while", "_Bool", "_Complex", "_Imaginary",
+];
+
+// A C99 header and source pair. Every symbol is prefixed with the network name,
+// so several exported networks can be linked into the same program.
+#[derive(Clone, Debug)]
+pub struct CSource {
+    name: String,
+    header: String,
+    source: String,
+}
+
+impl CSource {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_header(&self) -> &str {
+        &self.header
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    // Writes `<name>.h` and `<name>.c` into the directory.
+    pub fn save<P: AsRef<Path>>(&self, directory: P) -> io::Result<()> {
+        let directory = directory.as_ref();
+        fs::write(directory.join(format!("{}.h", self.name)), &self.header)?;
+        fs::write(directory.join(format!("{}.c", self.name)), &self.source)
+    }
+}
+
+impl FeedForwardNetwork {
+    pub fn to_c(&self, name: &str) -> Result<CSource, NeatError> {
+        generate(self.get_topology(), name, false)
+    }
+}
+
+impl RecurrentNetwork {
+    pub fn to_c(&self, name: &str) -> Result<CSource, NeatError> {
+        generate(self.get_topology(), name, true)
+    }
+}
+
+// The state holds the sensor values followed by the other neurons. A feed-forward
+// network evaluates the neurons in order and writes them in place, a recurrent one
+// computes every neuron from the previous step before storing the new values.
+fn generate(topology: &Topology, name: &str, recurrent: bool) -> Result<CSource, NeatError> {
+    check_identifier(name, C_KEYWORDS)?;
+    let guard = format!("{}_H", name.to_ascii_uppercase());
+    let n_nodes = topology.nodes.len();
+
+    let header = format!(
+"/* Generated by rust_neat from a {kind} network. */
+#ifndef {guard}
+#define {guard}
+
+#define {upper}_INPUTS {inputs}
+#define {upper}_OUTPUTS {outputs}
+
+typedef struct {{
+    double values[{values}];
+}} {name}_state_t;
+
+void {name}_reset({name}_state_t *s);
+void {name}_activate(const double *in, double *out, {name}_state_t *s);
+
+#endif
+",
+        kind = if recurrent { "recurrent" } else { "feed-forward" },
+        guard = guard,
+        upper = name.to_ascii_uppercase(),
+        inputs = topology.n_inputs,
+        outputs = topology.outputs.len(),
+        values = topology.get_n_values(),
+        name = name
+    );
+
+    let csr = Csr::new(topology);
+    let weights: Vec<String> = csr.weights.iter().map(|&weight| c_literal(weight)).collect();
+    let sources: Vec<String> = csr.sources.iter().map(u32::to_string).collect();
+    let offsets: Vec<String> = csr.offsets.iter().map(u32::to_string).collect();
+    let outputs: Vec<String> = csr.outputs.iter().map(u32::to_string).collect();
+
+    let store = if recurrent {
+        format!("next[i] = sigmoid(sum);\n    }}\n    for (i = 0; i < {}; i++) {{\n        s->values[{} + i] = next[i];", n_nodes, topology.n_inputs)
+    } else {
+        format!("s->values[{} + i] = sigmoid(sum);", topology.n_inputs)
+    };
+
+    let source = format!(
+"/* Generated by rust_neat from a {kind} network. */
+#include <math.h>
+#include \"{name}.h\"
+
+static const double {name}_weights[{n_links}] = {{ {weights} }};
+static const unsigned {name}_sources[{n_links}] = {{ {sources} }};
+static const unsigned {name}_offsets[{n_offsets}] = {{ {offsets} }};
+static const unsigned {name}_outputs[{n_outputs}] = {{ {outputs} }};
+
+static double sigmoid(double x)
+{{
+    return 1.0 / (1.0 + exp(-x));
+}}
+
+void {name}_reset({name}_state_t *s)
+{{
+    unsigned i;
+    for (i = 0; i < {n_values}; i++) {{
+        s->values[i] = 0.0;
+    }}
+}}
+
+void {name}_activate(const double *in, double *out, {name}_state_t *s)
+{{
+    unsigned i, j;{next}
+    for (i = 0; i < {n_inputs}; i++) {{
+        s->values[i] = in[i];
+    }}
+    for (i = 0; i < {n_nodes}; i++) {{
+        double sum = 0.0;
+        for (j = {name}_offsets[i]; j < {name}_offsets[i + 1]; j++) {{
+            sum += {name}_weights[j] * s->values[{name}_sources[j]];
+        }}
+        {store}
+    }}
+    for (i = 0; i < {n_outputs}; i++) {{
+        out[i] = s->values[{name}_outputs[i]];
+    }}
+}}
+",
+        kind = if recurrent { "recurrent" } else { "feed-forward" },
+        name = name,
+        // C99 has no zero-length arrays, so a network without links keeps a dummy entry.
+        n_links = weights.len().max(1),
+        weights = if weights.is_empty() { "0.0".to_string() } else { weights.join(", ") },
+        sources = if sources.is_empty() { "0".to_string() } else { sources.join(", ") },
+        n_offsets = offsets.len(),
+        offsets = offsets.join(", "),
+        n_outputs = outputs.len(),
+        outputs = outputs.join(", "),
+        n_values = topology.get_n_values(),
+        n_inputs = topology.n_inputs,
+        n_nodes = n_nodes,
+        next = if recurrent { format!("\n    double next[{}];", n_nodes) } else { String::new() },
+        store = store
+    );
+
+    Ok(CSource {
+        name: name.to_string(),
+        header,
+        source,
+    })
+}
+
+fn c_literal(value: f64) -> String {
+    if value.is_nan() {
+        "NAN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "INFINITY" } else { "-INFINITY" }.to_string()
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process::Command;
+    use rand::{ Rng, SeedableRng };
+    use crate::utils::NeatRng;
+    use crate::network::tests::{ genome, FEED_FORWARD, RECURRENT };
+
+    #[test]
+    fn test_c_source() {
+        let network = FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        let code = network.to_c("xor").unwrap();
+        assert!(code.get_header().contains("void xor_activate(const double *in, double *out, xor_state_t *s);"));
+        assert!(code.get_header().contains("#define XOR_INPUTS 2"));
+        assert!(code.get_source().contains("static const double xor_weights[4] = { 1.5, -2.0, 3.0, -0.75 };"));
+        assert!(code.get_source().contains("static const unsigned xor_offsets[3] = { 0, 2, 4 };"));
+
+        assert!(matches!(network.to_c("double"), Err(NeatError::InvalidIdentifier(_))));
+    }
+
+    // Builds both networks with the system C compiler and compares them with the
+    // phenotypes over a sequence of random inputs.
+    #[test]
+    fn test_compiled_code_matches() {
+        let mut feed_forward = FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        let mut recurrent = RecurrentNetwork::new(&genome(RECURRENT)).unwrap();
+        let mut rng = NeatRng::seed_from_u64(7);
+        let inputs: Vec<[f64; 2]> = (0..20).map(|_| [rng.gen_range(-3.0, 3.0), rng.gen_range(-3.0, 3.0)]).collect();
+
+        let directory = env::temp_dir().join(format!("rust_neat_c_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        feed_forward.to_c("ff").unwrap().save(&directory).unwrap();
+        recurrent.to_c("rnn").unwrap().save(&directory).unwrap();
+
+        let rows: Vec<String> = inputs.iter().map(|input| format!("{{ {:?}, {:?} }}", input[0], input[1])).collect();
+        let main = format!(
+"#include <stdio.h>
+#include \"ff.h\"
+#include \"rnn.h\"
+
+static const double inputs[{}][2] = {{ {} }};
+
+int main(void)
+{{
+    ff_state_t ff;
+    rnn_state_t rnn;
+    double out[1];
+    unsigned i;
+    ff_reset(&ff);
+    rnn_reset(&rnn);
+    for (i = 0; i < {}; i++) {{
+        ff_activate(inputs[i], out, &ff);
+        printf(\"%.17g \", out[0]);
+        rnn_activate(inputs[i], out, &rnn);
+        printf(\"%.17g\\n\", out[0]);
+    }}
+    return 0;
+}}
+",
+            inputs.len(), rows.join(", "), inputs.len()
+        );
+        fs::write(directory.join("main.c"), main).unwrap();
+
+        let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+        let binary = directory.join("generated");
+        let status = Command::new(compiler)
+            .current_dir(&directory)
+            .args(["-std=c99", "-pedantic", "-Wall", "-Werror", "-o"])
+            .arg(&binary)
+            .args(["main.c", "ff.c", "rnn.c", "-lm"])
+            .status()
+            .unwrap();
+        assert!(status.success(), "generated C code failed to compile");
+
+        let output = Command::new(&binary).output().unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let mut lines = stdout.lines();
+        for input in inputs.iter() {
+            let values: Vec<f64> = lines.next().unwrap().split(' ').map(|value| value.parse().unwrap()).collect();
+            // libm's exp may differ from Rust's in the last bit.
+            assert!((values[0] - feed_forward.activate(input)[0]).abs() < 1e-12);
+            assert!((values[1] - recurrent.activate(&input[..1])[0]).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::ops::{ Add, Mul };
+use crate::genome::Genome;
+use crate::NeatError;
+use super::Csr;
+use super::feed_forward::FeedForwardNetwork;
+
+pub trait Scalar: Copy + Default + Add<Output = Self> + Mul<Output = Self> {
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn sigmoid(self) -> Self;
+}
+
+impl Scalar for f32 {
+    fn from_f64(value: f64) -> f32 {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn sigmoid(self) -> f32 {
+        1.0 / (1.0 + (-self).exp())
+    }
+}
+
+impl Scalar for f64 {
+    fn from_f64(value: f64) -> f64 {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn sigmoid(self) -> f64 {
+        1.0 / (1.0 + (-self).exp())
+    }
+}
+
+// A feed-forward network flattened into contiguous arrays with the nodes stored in
+// evaluation order, so activation is two nested loops over slices without any lookups.
+#[derive(Clone, Debug)]
+pub struct CompiledNetwork<T: Scalar> {
+    csr: Csr<T>,
+    values: Vec<T>,
+}
+
+impl FeedForwardNetwork {
+    pub fn compile<T: Scalar>(&self) -> CompiledNetwork<T> {
+        let csr = Csr::new(self.get_topology()).map(|&weight| T::from_f64(weight));
+        CompiledNetwork {
+            values: vec![T::default(); csr.get_n_values()],
+            csr,
+        }
+    }
+}
+
+impl<T: Scalar> CompiledNetwork<T> {
+    pub fn new(genome: &Genome) -> Result<CompiledNetwork<T>, NeatError> {
+        Ok(FeedForwardNetwork::new(genome)?.compile())
+    }
+
+    pub fn get_n_inputs(&self) -> usize {
+        self.csr.n_inputs
+    }
+
+    pub fn get_n_outputs(&self) -> usize {
+        self.csr.outputs.len()
+    }
+
+    pub(crate) fn get_csr(&self) -> &Csr<T> {
+        &self.csr
+    }
+
+    pub fn activate(&mut self, inputs: &[T]) -> Vec<T> {
+        let mut outputs = vec![T::default(); self.csr.outputs.len()];
+        self.activate_into(inputs, &mut outputs);
+        outputs
+    }
+
+    // Allocation-free variant for the hot path.
+    pub fn activate_into(&mut self, inputs: &[T], outputs: &mut [T]) {
+        let Csr { n_inputs, offsets, sources, weights, outputs: output_indices } = &self.csr;
+        let n_inputs = *n_inputs;
+        assert_eq!(inputs.len(), n_inputs, "expected {} inputs, got {}", n_inputs, inputs.len());
+        assert_eq!(outputs.len(), output_indices.len(), "expected room for {} outputs, got {}", output_indices.len(), outputs.len());
+
+        let values = &mut self.values[..];
+        values[..n_inputs].copy_from_slice(inputs);
+        let mut start = 0;
+        for (node, &end) in offsets[1..].iter().enumerate() {
+            let end = end as usize;
+            let sum = sources[start..end].iter()
+                .zip(weights[start..end].iter())
+                .fold(T::default(), |sum, (&source, &weight)| sum + weight * values[source as usize]);
+            values[n_inputs + node] = sum.sigmoid();
+            start = end;
+        }
+
+        for (output, &index) in outputs.iter_mut().zip(output_indices.iter()) {
+            *output = values[index as usize];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::tests::{ genome, FEED_FORWARD, RECURRENT };
+
+    #[test]
+    fn test_matches_feed_forward() {
+        let mut network = FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        let mut compiled: CompiledNetwork<f64> = network.compile();
+        let mut single: CompiledNetwork<f32> = CompiledNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        assert_eq!(compiled.get_n_inputs(), 2);
+
+        let mut outputs = [0.0];
+        for input in [[0.0, 0.0], [1.0, -1.0], [0.25, 3.5], [-2.0, 0.125]].iter() {
+            let expected = network.activate(input);
+            compiled.activate_into(input, &mut outputs);
+            assert_eq!(outputs.to_vec(), expected);
+
+            let output = single.activate(&[input[0] as f32, input[1] as f32]);
+            assert!((output[0].to_f64() - expected[0]).abs() < 1e-6);
+        }
+
+        assert!(CompiledNetwork::<f64>::new(&genome(RECURRENT)).is_err());
+    }
+}
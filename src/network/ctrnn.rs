@@ -0,0 +1,220 @@
+use crate::genome::{ Genome, Gene };
+use crate::genome::neuron_gene;
+use crate::NeatError;
+use super::{ Topology, sigmoid };
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Integration {
+    Euler,
+    RungeKutta4,
+}
+
+// Continuous-time recurrent network. Each non-sensor neuron keeps a state y with
+//   time_constant * dy/dt = -y + sum(weight * source)
+// where a sensor source is its input and a neuron source fires sigmoid(y + bias).
+// The outputs are the firing rates of the output neurons.
+#[derive(Clone, Debug)]
+pub struct CtrnnNetwork {
+    topology: Topology,
+    biases: Vec<f64>,
+    time_constants: Vec<f64>,
+    integration: Integration,
+    time_step: f64,
+    state: Vec<f64>,
+    stage: Vec<f64>,
+    slopes: [Vec<f64>; 4],
+}
+
+impl CtrnnNetwork {
+    pub fn new(genome: &Genome) -> Result<CtrnnNetwork, NeatError> {
+        let neurons: Vec<_> = genome.iter_neurons()
+            .filter(|neuron| neuron.get_class() != neuron_gene::SENSOR)
+            .collect();
+        let order: Vec<u32> = neurons.iter().map(|neuron| neuron.get_innovation_number()).collect();
+        let topology = Topology::new(genome, &order)?;
+        let state = vec![0.0; neurons.len()];
+
+        Ok(CtrnnNetwork {
+            topology,
+            biases: neurons.iter().map(|neuron| neuron.get_bias()).collect(),
+            time_constants: neurons.iter().map(|neuron| neuron.get_time_constant()).collect(),
+            integration: Integration::Euler,
+            time_step: 0.01,
+            stage: state.clone(),
+            slopes: [state.clone(), state.clone(), state.clone(), state.clone()],
+            state,
+        })
+    }
+
+    pub fn set_integration(&mut self, integration: Integration) {
+        self.integration = integration;
+    }
+
+    pub fn set_time_step(&mut self, time_step: f64) {
+        assert!(time_step > 0.0 && time_step.is_finite(), "time step must be positive, got {}", time_step);
+        self.time_step = time_step;
+    }
+
+    pub fn get_integration(&self) -> Integration {
+        self.integration
+    }
+
+    pub fn get_time_step(&self) -> f64 {
+        self.time_step
+    }
+
+    pub fn get_n_inputs(&self) -> usize {
+        self.topology.n_inputs
+    }
+
+    pub fn get_n_outputs(&self) -> usize {
+        self.topology.outputs.len()
+    }
+
+    pub fn reset(&mut self) {
+        self.state.iter_mut().for_each(|value| *value = 0.0);
+    }
+
+    // Advances the network by one time step with the inputs held constant.
+    pub fn activate(&mut self, inputs: &[f64]) -> Vec<f64> {
+        self.topology.check_inputs(inputs);
+        self.step(inputs);
+        self.read_outputs()
+    }
+
+    // Holds the inputs for `duration`, rounded to a whole number of time steps.
+    pub fn advance(&mut self, inputs: &[f64], duration: f64) -> Vec<f64> {
+        self.topology.check_inputs(inputs);
+        let steps = (duration / self.time_step).round().max(0.0) as usize;
+        for _ in 0..steps {
+            self.step(inputs);
+        }
+        self.read_outputs()
+    }
+
+    fn step(&mut self, inputs: &[f64]) {
+        let dt = self.time_step;
+        let [k1, k2, k3, k4] = &mut self.slopes;
+        let dynamics = Dynamics { topology: &self.topology, biases: &self.biases, time_constants: &self.time_constants, inputs };
+
+        match self.integration {
+            Integration::Euler => {
+                dynamics.derivative(&self.state, k1);
+                offset(&mut self.state, k1, dt);
+            },
+            Integration::RungeKutta4 => {
+                dynamics.derivative(&self.state, k1);
+                self.stage.copy_from_slice(&self.state);
+                offset(&mut self.stage, k1, 0.5 * dt);
+                dynamics.derivative(&self.stage, k2);
+                self.stage.copy_from_slice(&self.state);
+                offset(&mut self.stage, k2, 0.5 * dt);
+                dynamics.derivative(&self.stage, k3);
+                self.stage.copy_from_slice(&self.state);
+                offset(&mut self.stage, k3, dt);
+                dynamics.derivative(&self.stage, k4);
+
+                let slopes = k1.iter().zip(k2.iter()).zip(k3.iter()).zip(k4.iter());
+                for (value, (((k1, k2), k3), k4)) in self.state.iter_mut().zip(slopes) {
+                    *value += dt / 6.0 * (k1 + 2.0 * k2 + 2.0 * k3 + k4);
+                }
+            }
+        }
+    }
+
+    fn read_outputs(&self) -> Vec<f64> {
+        let n_inputs = self.topology.n_inputs;
+        self.topology.outputs.iter()
+            .map(|&index| sigmoid(self.state[index - n_inputs] + self.biases[index - n_inputs]))
+            .collect()
+    }
+}
+
+fn offset(values: &mut [f64], slopes: &[f64], scale: f64) {
+    for (value, slope) in values.iter_mut().zip(slopes.iter()) {
+        *value += scale * slope;
+    }
+}
+
+struct Dynamics<'a> {
+    topology: &'a Topology,
+    biases: &'a [f64],
+    time_constants: &'a [f64],
+    inputs: &'a [f64],
+}
+
+impl<'a> Dynamics<'a> {
+    fn derivative(&self, state: &[f64], slopes: &mut [f64]) {
+        let n_inputs = self.topology.n_inputs;
+        for (position, node) in self.topology.nodes.iter().enumerate() {
+            let sum: f64 = node.links.iter()
+                .map(|&(source, weight)| weight * match source.checked_sub(n_inputs) {
+                    Some(node) => sigmoid(state[node] + self.biases[node]),
+                    None => self.inputs[source]
+                })
+                .sum();
+            slopes[position] = (sum - state[position]) / self.time_constants[position];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genome::neuron_gene::NeuronGene;
+    use crate::network::tests::{ genome, RECURRENT };
+
+    const RELAY: &str = "
+        neuron 0 sensor
+        neuron 1 output
+        connection 2 0 1 1 enabled
+    ";
+
+    #[test]
+    fn test_matches_analytic_solution() {
+        // With a constant input of 1 the output state follows y(t) = 1 - exp(-t / time_constant).
+        let expected = sigmoid(1.0 - (-1.0f64).exp());
+        let mut errors = Vec::new();
+        for &integration in &[Integration::Euler, Integration::RungeKutta4] {
+            let mut network = CtrnnNetwork::new(&genome(RELAY)).unwrap();
+            network.set_integration(integration);
+            let output = network.advance(&[1.0], 1.0);
+            errors.push((output[0] - expected).abs());
+        }
+        assert!(errors[0] < 1e-3);
+        assert!(errors[1] < 1e-10);
+
+        let slow = genome("
+            neuron 0 sensor
+            neuron 1 output bias -0.5 time_constant 2
+            connection 2 0 1 1 enabled
+        ");
+        let mut network = CtrnnNetwork::new(&slow).unwrap();
+        network.set_integration(Integration::RungeKutta4);
+        network.set_time_step(0.05);
+        let output = network.advance(&[1.0], 2.0);
+        assert!((output[0] - sigmoid(0.5 - (-1.0f64).exp())).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_activate_and_reset() {
+        let mut network = CtrnnNetwork::new(&genome(RECURRENT)).unwrap();
+        assert_eq!((network.get_n_inputs(), network.get_n_outputs()), (1, 1));
+
+        // From rest neuron 3 fires at 0.5, so one Euler step moves the output state by 0.01 * 0.5.
+        let first = network.activate(&[1.0]);
+        assert_eq!(first, vec![sigmoid(0.005)]);
+        let later = network.advance(&[1.0], 0.5);
+        assert_ne!(later, first);
+
+        network.reset();
+        assert_eq!(network.activate(&[1.0]), first);
+    }
+
+    #[test]
+    fn test_time_constants_must_be_positive() {
+        let mut neuron = NeuronGene::new(0, neuron_gene::OUTPUT);
+        neuron.set_time_constant(0.0);
+        assert!(matches!(Genome::from_genes(vec![neuron], vec![]), Err(NeatError::TimeConstant { neuron: 0, .. })));
+    }
+}
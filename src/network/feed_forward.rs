@@ -0,0 +1,181 @@
+use std::collections::{ BTreeSet, HashMap };
+use crate::genome::{ Genome, Gene };
+use crate::genome::neuron_gene;
+use crate::NeatError;
+use super::{ Topology, activate_row, sigmoid };
+
+#[derive(Clone, Debug)]
+pub struct FeedForwardNetwork {
+    topology: Topology,
+    values: Vec<f64>,
+}
+
+impl FeedForwardNetwork {
+    pub fn new(genome: &Genome) -> Result<FeedForwardNetwork, NeatError> {
+        let topology = Topology::new(genome, &evaluation_order(genome)?)?;
+        let values = vec![0.0; topology.get_n_values()];
+
+        Ok(FeedForwardNetwork {
+            topology,
+            values,
+        })
+    }
+
+    pub fn get_n_inputs(&self) -> usize {
+        self.topology.n_inputs
+    }
+
+    pub fn get_n_outputs(&self) -> usize {
+        self.topology.outputs.len()
+    }
+
+    pub(crate) fn get_topology(&self) -> &Topology {
+        &self.topology
+    }
+
+    pub fn activate(&mut self, inputs: &[f64]) -> Vec<f64> {
+        self.topology.check_inputs(inputs);
+        let n_inputs = self.topology.n_inputs;
+        self.values[..n_inputs].copy_from_slice(inputs);
+
+        for (position, node) in self.topology.nodes.iter().enumerate() {
+            let sum: f64 = node.links.iter().map(|&(source, weight)| weight * self.values[source]).sum();
+            self.values[n_inputs + position] = sigmoid(sum);
+        }
+
+        self.topology.outputs.iter().map(|&index| self.values[index]).collect()
+    }
+
+    pub fn activate_batch<R: AsRef<[f64]>>(&mut self, inputs: &[R]) -> Vec<Vec<f64>> {
+        let batch = inputs.len();
+        let values = self.run_batch(inputs.iter().map(AsRef::as_ref), batch);
+        (0..batch).map(|sample| self.topology.read_outputs(batch, sample, &values).collect()).collect()
+    }
+
+    // Row-major variant: `inputs` holds `get_n_inputs` values per sample and the
+    // outputs come back the same way.
+    pub fn activate_batch_flat(&mut self, inputs: &[f64]) -> Vec<f64> {
+        let n_inputs = self.topology.n_inputs.max(1);
+        assert_eq!(inputs.len() % n_inputs, 0, "{} inputs do not form rows of {}", inputs.len(), n_inputs);
+        let batch = inputs.len() / n_inputs;
+        let values = self.run_batch(inputs.chunks(n_inputs), batch);
+        (0..batch).flat_map(|sample| self.topology.read_outputs(batch, sample, &values)).collect()
+    }
+
+    // The nodes are in evaluation order, so every source row of a node lies before its own.
+    fn run_batch<'a, I: Iterator<Item = &'a [f64]>>(&self, rows: I, batch: usize) -> Vec<f64> {
+        let mut values = vec![0.0; self.topology.get_n_values() * batch];
+        self.topology.write_inputs(rows, batch, &mut values);
+        for (position, node) in self.topology.nodes.iter().enumerate() {
+            let (computed, rest) = values.split_at_mut((self.topology.n_inputs + position) * batch);
+            activate_row(node, computed, batch, &mut rest[..batch]);
+        }
+        values
+    }
+}
+
+// Kahn's algorithm over the enabled connections, always taking the ready neuron
+// with the lowest (class, innovation) so the order follows the classes whenever it can.
+fn evaluation_order(genome: &Genome) -> Result<Vec<u32>, NeatError> {
+    let key = |neuron: u32| (genome.get_neuron(neuron).map_or(0, |neuron| neuron.get_class()), neuron);
+
+    let mut incoming: HashMap<u32, usize> = genome.iter_neurons()
+        .filter(|neuron| neuron.get_class() != neuron_gene::SENSOR)
+        .map(|neuron| (neuron.get_innovation_number(), 0))
+        .collect();
+    let mut outgoing: HashMap<u32, Vec<u32>> = HashMap::new();
+    for connection in genome.iter_connections().filter(|connection| connection.is_enabled()) {
+        // Sensors are always ready, so only links from other neurons hold a neuron back.
+        if !incoming.contains_key(&connection.get_neuron_in()) {
+            continue;
+        }
+        if let Some(count) = incoming.get_mut(&connection.get_neuron_out()) {
+            *count += 1;
+            outgoing.entry(connection.get_neuron_in()).or_default().push(connection.get_neuron_out());
+        }
+    }
+
+    let mut ready: BTreeSet<(u32, u32)> = incoming.iter()
+        .filter(|(_, &count)| count == 0)
+        .map(|(&neuron, _)| key(neuron))
+        .collect();
+    let mut order = Vec::with_capacity(incoming.len());
+    while let Some(&next) = ready.iter().next() {
+        ready.remove(&next);
+        let neuron = next.1;
+        order.push(neuron);
+        for target in outgoing.get(&neuron).into_iter().flatten() {
+            if let Some(count) = incoming.get_mut(target) {
+                *count -= 1;
+                if *count == 0 {
+                    ready.insert(key(*target));
+                }
+            }
+        }
+    }
+
+    match incoming.iter().filter(|&(_, &count)| count > 0).map(|(&neuron, _)| neuron).min() {
+        Some(neuron) => Err(NeatError::Cycle { neuron }),
+        None => Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::tests::{ genome, FEED_FORWARD, RECURRENT };
+
+    #[test]
+    fn test_activate() {
+        let mut network = FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        assert_eq!(network.get_n_inputs(), 2);
+        assert_eq!(network.get_n_outputs(), 1);
+
+        let hidden = sigmoid(1.5 * 0.5 + 2.0);
+        let expected = sigmoid(3.0 * hidden - 0.75 * 0.5);
+        assert_eq!(network.activate(&[0.5, -1.0]), vec![expected]);
+        assert_eq!(network.activate(&[0.0, 0.0]), vec![sigmoid(3.0 * 0.5)]);
+    }
+
+    #[test]
+    fn test_activate_batch() {
+        let mut network = FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        let inputs = vec![vec![0.0, 0.0], vec![1.0, -1.0], vec![0.25, 3.5], vec![-2.0, 0.125]];
+        let expected: Vec<Vec<f64>> = inputs.iter().map(|input| network.activate(input)).collect();
+
+        assert_eq!(network.activate_batch(&inputs), expected);
+        assert_eq!(network.activate_batch_flat(&inputs.concat()), expected.concat());
+        assert!(network.activate_batch::<Vec<f64>>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_evaluation_order() {
+        // The hidden neuron with the higher class feeds the lower one.
+        let genome = genome("
+            neuron 0 sensor
+            neuron 1 output
+            neuron 2 hidden 10
+            neuron 3 hidden 20
+            connection 4 0 3 1 enabled
+            connection 5 3 2 1 enabled
+            connection 6 2 1 1 enabled
+        ");
+        let network = FeedForwardNetwork::new(&genome).unwrap();
+        let order: Vec<u32> = network.get_topology().nodes.iter().map(|node| node.neuron).collect();
+        assert_eq!(order, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_cycle() {
+        match FeedForwardNetwork::new(&genome(RECURRENT)) {
+            Err(NeatError::Cycle { neuron }) => assert_eq!(neuron, 1),
+            other => panic!("expected a cycle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 2 inputs, got 1")]
+    fn test_input_count() {
+        FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap().activate(&[1.0]);
+    }
+}
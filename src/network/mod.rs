@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use crate::genome::{ Genome, Gene };
+use crate::genome::neuron_gene;
+use crate::NeatError;
+
+pub mod feed_forward;
+pub mod recurrent;
+pub mod ctrnn;
+pub mod c;
+pub mod onnx;
+pub mod quantized;
+pub mod compiled;
+pub mod rust;
+
+pub(crate) fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+// Enabled links into one non-sensor neuron, as (value index, weight) pairs.
+#[derive(Clone, Debug)]
+pub(crate) struct Node {
+    pub(crate) neuron: u32,
+    pub(crate) links: Vec<(usize, f64)>,
+}
+
+// Wiring shared by the phenotypes. The value buffer holds the sensors in
+// innovation order followed by the other neurons in the order given to `new`,
+// which is the evaluation order for feed-forward networks. Only the weights are
+// kept: neuron biases and time constants are read by CtrnnNetwork alone, so the
+// feed-forward, recurrent and exported networks ignore them.
+#[derive(Clone, Debug)]
+pub(crate) struct Topology {
+    pub(crate) n_inputs: usize,
+    pub(crate) nodes: Vec<Node>,
+    pub(crate) outputs: Vec<usize>,
+}
+
+impl Topology {
+    pub(crate) fn new(genome: &Genome, order: &[u32]) -> Result<Topology, NeatError> {
+        let sensors: Vec<u32> = genome.iter_neurons()
+            .filter(|neuron| neuron.get_class() == neuron_gene::SENSOR)
+            .map(|neuron| neuron.get_innovation_number())
+            .collect();
+        let index: HashMap<u32, usize> = sensors.iter().chain(order.iter())
+            .enumerate()
+            .map(|(index, &neuron)| (neuron, index))
+            .collect();
+
+        let mut nodes: Vec<Node> = order.iter().map(|&neuron| Node { neuron, links: Vec::new() }).collect();
+        for connection in genome.iter_connections().filter(|connection| connection.is_enabled()) {
+            let innovation = connection.get_innovation_number();
+            let source = *index.get(&connection.get_neuron_in())
+                .ok_or(NeatError::DanglingNeuron { connection: innovation, neuron: connection.get_neuron_in() })?;
+            let target = *index.get(&connection.get_neuron_out())
+                .ok_or(NeatError::DanglingNeuron { connection: innovation, neuron: connection.get_neuron_out() })?;
+            // Sensor values are set from the inputs, so links into them have no effect.
+            if target >= sensors.len() {
+                nodes[target - sensors.len()].links.push((source, connection.get_weight()));
+            }
+        }
+
+        let outputs = genome.iter_neurons()
+            .filter(|neuron| neuron.get_class() == neuron_gene::OUTPUT)
+            .filter_map(|neuron| index.get(&neuron.get_innovation_number()).copied())
+            .collect();
+
+        Ok(Topology {
+            n_inputs: sensors.len(),
+            nodes,
+            outputs,
+        })
+    }
+
+    pub(crate) fn get_n_values(&self) -> usize {
+        self.n_inputs + self.nodes.len()
+    }
+
+    pub(crate) fn check_inputs(&self, inputs: &[f64]) {
+        assert_eq!(inputs.len(), self.n_inputs, "expected {} inputs, got {}", self.n_inputs, inputs.len());
+    }
+
+    // Batched buffers hold one row of `batch` samples per value index, so writing the
+    // inputs is a transpose and each link becomes a loop over a contiguous row.
+    pub(crate) fn write_inputs<'a, I>(&self, rows: I, batch: usize, values: &mut [f64])
+    where
+        I: Iterator<Item = &'a [f64]>
+    {
+        for (sample, row) in rows.enumerate() {
+            self.check_inputs(row);
+            for (input, &value) in row.iter().enumerate() {
+                values[input * batch + sample] = value;
+            }
+        }
+    }
+
+    pub(crate) fn read_outputs<'a>(&'a self, batch: usize, sample: usize, values: &'a [f64]) -> impl Iterator<Item = f64> + 'a {
+        let values = &values[..self.get_n_values() * batch];
+        self.outputs.iter().map(move |&index| values[index * batch + sample])
+    }
+}
+
+// A topology flattened into compressed sparse rows: the links into node `i` are
+// `sources[offsets[i]..offsets[i + 1]]` with the matching `weights`. The compiled,
+// quantized and C phenotypes are all built from this layout.
+#[derive(Clone, Debug)]
+pub(crate) struct Csr<T> {
+    pub(crate) n_inputs: usize,
+    pub(crate) offsets: Vec<u32>,
+    pub(crate) sources: Vec<u32>,
+    pub(crate) weights: Vec<T>,
+    pub(crate) outputs: Vec<u32>,
+}
+
+impl Csr<f64> {
+    pub(crate) fn new(topology: &Topology) -> Csr<f64> {
+        let mut offsets = Vec::with_capacity(topology.nodes.len() + 1);
+        let mut sources = Vec::new();
+        let mut weights = Vec::new();
+        offsets.push(0);
+        for node in topology.nodes.iter() {
+            for &(source, weight) in node.links.iter() {
+                sources.push(source as u32);
+                weights.push(weight);
+            }
+            offsets.push(sources.len() as u32);
+        }
+
+        Csr {
+            n_inputs: topology.n_inputs,
+            offsets,
+            sources,
+            weights,
+            outputs: topology.outputs.iter().map(|&index| index as u32).collect(),
+        }
+    }
+}
+
+impl<T> Csr<T> {
+    pub(crate) fn map<U, F>(&self, f: F) -> Csr<U>
+    where
+        F: FnMut(&T) -> U
+    {
+        Csr {
+            n_inputs: self.n_inputs,
+            offsets: self.offsets.clone(),
+            sources: self.sources.clone(),
+            weights: self.weights.iter().map(f).collect(),
+            outputs: self.outputs.clone(),
+        }
+    }
+
+    pub(crate) fn get_n_nodes(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub(crate) fn get_n_values(&self) -> usize {
+        self.n_inputs + self.get_n_nodes()
+    }
+
+    // The links into each node in order, as matching source and weight slices.
+    pub(crate) fn iter_nodes(&self) -> impl Iterator<Item = (&[u32], &[T])> + '_ {
+        self.offsets.windows(2).map(move |bounds| {
+            let (start, end) = (bounds[0] as usize, bounds[1] as usize);
+            (&self.sources[start..end], &self.weights[start..end])
+        })
+    }
+}
+
+// Evaluates one node for the whole batch. Every sample sums its links in the same
+// order as the unbatched `activate`, so the results are identical.
+pub(crate) fn activate_row(node: &Node, values: &[f64], batch: usize, row: &mut [f64]) {
+    row.iter_mut().for_each(|value| *value = 0.0);
+    for &(source, weight) in node.links.iter() {
+        let inputs = &values[source * batch..(source + 1) * batch];
+        for (value, &input) in row.iter_mut().zip(inputs.iter()) {
+            *value += weight * input;
+        }
+    }
+    row.iter_mut().for_each(|value| *value = sigmoid(*value));
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv",
+    "try", "typeof", "unsized", "virtual", "yield",
+];
+
+// Names handed to the code generators end up verbatim in the generated source.
+pub(crate) fn check_identifier(name: &str, keywords: &[&str]) -> Result<(), NeatError> {
+    let mut chars = name.chars();
+    let valid = match chars.next() {
+        Some(first) => (first.is_ascii_alphabetic() || first == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && name != "_",
+        None => false
+    };
+    if valid && !keywords.contains(&name) {
+        Ok(())
+    } else {
+        Err(NeatError::InvalidIdentifier(name.to_string()))
+    }
+}
+
+// `w0 * a + w1 * b - w2 * c` for the links of a node, or `0.0` without any.
+pub(crate) fn weighted_sum<L, O>(links: &[(usize, f64)], literal: L, operand: O) -> String
+where
+    L: Fn(f64) -> String,
+    O: Fn(usize) -> String
+{
+    if links.is_empty() {
+        return literal(0.0);
+    }
+
+    let mut sum = String::new();
+    for (position, &(source, weight)) in links.iter().enumerate() {
+        let negative = weight.is_sign_negative() && !weight.is_nan();
+        match (position, negative) {
+            (0, true) => sum.push('-'),
+            (0, false) => {},
+            (_, true) => sum.push_str(" - "),
+            (_, false) => sum.push_str(" + "),
+        }
+        sum.push_str(&format!("{} * {}", literal(weight.abs()), operand(source)));
+    }
+    sum
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::genome::Genome;
+
+    // Two sensors, a hidden neuron and one output.
+    pub(crate) const FEED_FORWARD: &str = "
+        neuron 0 sensor
+        neuron 1 sensor
+        neuron 2 output
+        neuron 3 hidden 100
+        connection 4 0 3 1.5 enabled
+        connection 5 1 3 -2 enabled
+        connection 6 3 2 3 enabled
+        connection 7 0 2 -0.75 enabled
+        connection 8 1 2 4 disabled
+    ";
+
+    // The hidden neurons feed each other and the output feeds back into the first one.
+    pub(crate) const RECURRENT: &str = "
+        neuron 0 sensor
+        neuron 1 output
+        neuron 2 hidden 10
+        neuron 3 hidden 20
+        connection 4 0 2 1.25 enabled
+        connection 5 2 3 -0.5 enabled
+        connection 6 3 2 2 enabled
+        connection 7 3 1 1 enabled
+        connection 8 1 2 -1.5 enabled
+    ";
+
+    pub(crate) fn genome(text: &str) -> Genome {
+        text.parse().unwrap()
+    }
+}
@@ -0,0 +1,313 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use super::Topology;
+use super::feed_forward::FeedForwardNetwork;
+
+const IR_VERSION: u64 = 7;
+const OPSET_VERSION: u64 = 13;
+const FLOAT: u64 = 1;
+const INT64: u64 = 7;
+const ATTRIBUTE_INT: u64 = 2;
+
+// Minimal protobuf writer covering the wire types the ONNX messages need.
+struct Message {
+    bytes: Vec<u8>,
+}
+
+impl Message {
+    fn new() -> Message {
+        Message { bytes: Vec::new() }
+    }
+
+    fn key(&mut self, field: u32, wire_type: u8) {
+        self.raw_varint(u64::from(field) << 3 | u64::from(wire_type));
+    }
+
+    fn raw_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+
+    fn varint(&mut self, field: u32, value: u64) -> &mut Message {
+        self.key(field, 0);
+        self.raw_varint(value);
+        self
+    }
+
+    fn bytes(&mut self, field: u32, bytes: &[u8]) -> &mut Message {
+        self.key(field, 2);
+        self.raw_varint(bytes.len() as u64);
+        self.bytes.extend_from_slice(bytes);
+        self
+    }
+
+    fn string(&mut self, field: u32, value: &str) -> &mut Message {
+        self.bytes(field, value.as_bytes())
+    }
+
+    fn message(&mut self, field: u32, message: &Message) -> &mut Message {
+        self.bytes(field, &message.bytes)
+    }
+}
+
+// Each layer holds the neurons of one topological depth. The running value
+// `h{l}` concatenates the inputs with the outputs of every layer so far, so a
+// link that skips layers is just another row of the next masked weight matrix:
+//
+//     z{l} = MatMul(h{l-1}, W{l})    h{l} = Concat(h{l-1}, Sigmoid(z{l}))
+//
+// and the outputs are gathered from the last `h` at the end.
+impl FeedForwardNetwork {
+    pub fn to_onnx(&self) -> Vec<u8> {
+        let topology = self.get_topology();
+        let layers = layers(topology);
+
+        let mut position = vec![0; topology.get_n_values()];
+        for (index, position) in position.iter_mut().enumerate().take(topology.n_inputs) {
+            *position = index;
+        }
+        let mut width = topology.n_inputs;
+        let mut graph = Message::new();
+        graph.string(2, "neat");
+
+        let mut previous = "input".to_string();
+        for (layer, nodes) in layers.iter().enumerate().skip(1) {
+            let mut weights = vec![0.0f32; width * nodes.len()];
+            for (column, &node) in nodes.iter().enumerate() {
+                for &(source, weight) in topology.nodes[node].links.iter() {
+                    weights[position[source] * nodes.len() + column] += weight as f32;
+                }
+                position[topology.n_inputs + node] = width + column;
+            }
+
+            let raw: Vec<u8> = weights.iter().flat_map(|weight| weight.to_le_bytes()).collect();
+            graph.message(5, &tensor(&format!("W{}", layer), &[width, nodes.len()], FLOAT, &raw));
+
+            let (z, y, h) = (format!("z{}", layer), format!("y{}", layer), format!("h{}", layer));
+            graph.message(1, &node("MatMul", &[&previous, &format!("W{}", layer)], &z, None));
+            graph.message(1, &node("Sigmoid", &[&z], &y, None));
+            graph.message(1, &node("Concat", &[&previous, &y], &h, Some(("axis", 1))));
+            previous = h;
+            width += nodes.len();
+        }
+
+        let indices: Vec<u8> = topology.outputs.iter()
+            .flat_map(|&index| (position[index] as i64).to_le_bytes())
+            .collect();
+        graph.message(5, &tensor("output_indices", &[topology.outputs.len()], INT64, &indices));
+        graph.message(1, &node("Gather", &[&previous, "output_indices"], "output", Some(("axis", 1))));
+        graph.message(11, &value_info("input", topology.n_inputs));
+        graph.message(12, &value_info("output", topology.outputs.len()));
+
+        let mut opset = Message::new();
+        opset.string(1, "").varint(2, OPSET_VERSION);
+        let mut model = Message::new();
+        model.varint(1, IR_VERSION)
+            .string(2, "rust_neat")
+            .string(3, env!("CARGO_PKG_VERSION"))
+            .message(7, &graph)
+            .message(8, &opset);
+        model.bytes
+    }
+
+    pub fn save_onnx<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_onnx())
+    }
+}
+
+// Indices into `topology.nodes` grouped by depth; layer 0 stands for the inputs.
+fn layers(topology: &Topology) -> Vec<Vec<usize>> {
+    let mut depth = vec![0; topology.get_n_values()];
+    let mut layers = vec![Vec::new()];
+    for (node_index, node) in topology.nodes.iter().enumerate() {
+        let value = 1 + node.links.iter().map(|&(source, _)| depth[source]).max().unwrap_or(0);
+        depth[topology.n_inputs + node_index] = value;
+        if layers.len() <= value {
+            layers.resize(value + 1, Vec::new());
+        }
+        layers[value].push(node_index);
+    }
+    layers
+}
+
+fn tensor(name: &str, dims: &[usize], data_type: u64, raw: &[u8]) -> Message {
+    let mut tensor = Message::new();
+    for &dim in dims {
+        tensor.varint(1, dim as u64);
+    }
+    tensor.varint(2, data_type).string(8, name).bytes(9, raw);
+    tensor
+}
+
+fn node(op_type: &str, inputs: &[&str], output: &str, attribute: Option<(&str, u64)>) -> Message {
+    let mut node = Message::new();
+    for input in inputs {
+        node.string(1, input);
+    }
+    node.string(2, output).string(3, output).string(4, op_type);
+    if let Some((name, value)) = attribute {
+        let mut attribute = Message::new();
+        attribute.string(1, name).varint(3, value).varint(20, ATTRIBUTE_INT);
+        node.message(5, &attribute);
+    }
+    node
+}
+
+// A float tensor of shape [batch, width].
+fn value_info(name: &str, width: usize) -> Message {
+    let mut batch = Message::new();
+    batch.string(2, "batch");
+    let mut columns = Message::new();
+    columns.varint(1, width as u64);
+    let mut shape = Message::new();
+    shape.message(1, &batch).message(1, &columns);
+
+    let mut tensor_type = Message::new();
+    tensor_type.varint(1, FLOAT).message(2, &shape);
+    let mut value_type = Message::new();
+    value_type.message(1, &tensor_type);
+    let mut value_info = Message::new();
+    value_info.string(1, name).message(2, &value_type);
+    value_info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::convert::TryInto;
+    use crate::network::tests::{ genome, FEED_FORWARD };
+
+    enum Field<'a> {
+        Varint(u64),
+        Bytes(&'a [u8]),
+    }
+
+    fn read_varint(bytes: &[u8], offset: &mut usize) -> u64 {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = bytes[*offset];
+            *offset += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte < 0x80 {
+                return value;
+            }
+            shift += 7;
+        }
+    }
+
+    fn fields(bytes: &[u8]) -> Vec<(u32, Field<'_>)> {
+        let mut fields = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let key = read_varint(bytes, &mut offset);
+            let field = match key & 7 {
+                0 => Field::Varint(read_varint(bytes, &mut offset)),
+                2 => {
+                    let length = read_varint(bytes, &mut offset) as usize;
+                    offset += length;
+                    Field::Bytes(&bytes[offset - length..offset])
+                },
+                wire_type => panic!("unexpected wire type {}", wire_type),
+            };
+            fields.push(((key >> 3) as u32, field));
+        }
+        fields
+    }
+
+    fn strings(bytes: &[u8], number: u32) -> Vec<String> {
+        fields(bytes).into_iter().filter_map(|(field, value)| match value {
+            Field::Bytes(bytes) if field == number => Some(String::from_utf8(bytes.to_vec()).unwrap()),
+            _ => None
+        }).collect()
+    }
+
+    fn submessages(bytes: &[u8], number: u32) -> Vec<&[u8]> {
+        fields(bytes).into_iter().filter_map(|(field, value)| match value {
+            Field::Bytes(bytes) if field == number => Some(bytes),
+            _ => None
+        }).collect()
+    }
+
+    fn varints(bytes: &[u8], number: u32) -> Vec<u64> {
+        fields(bytes).into_iter().filter_map(|(field, value)| match value {
+            Field::Varint(value) if field == number => Some(value),
+            _ => None
+        }).collect()
+    }
+
+    // Evaluates the graph on one sample with a reader that only knows the ops the exporter emits.
+    fn run(model: &[u8], input: &[f64]) -> Vec<f64> {
+        let graph = submessages(model, 7)[0];
+        let mut values: HashMap<String, (Vec<usize>, Vec<f64>)> = HashMap::new();
+        values.insert("input".to_string(), (vec![input.len()], input.to_vec()));
+
+        for tensor in submessages(graph, 5) {
+            let dims: Vec<usize> = varints(tensor, 1).into_iter().map(|dim| dim as usize).collect();
+            let raw = submessages(tensor, 9)[0];
+            let data: Vec<f64> = match varints(tensor, 2)[0] {
+                FLOAT => raw.chunks(4).map(|chunk| f64::from(f32::from_le_bytes(chunk.try_into().unwrap()))).collect(),
+                INT64 => raw.chunks(8).map(|chunk| i64::from_le_bytes(chunk.try_into().unwrap()) as f64).collect(),
+                data_type => panic!("unexpected data type {}", data_type),
+            };
+            values.insert(strings(tensor, 8)[0].clone(), (dims, data));
+        }
+
+        for node in submessages(graph, 1) {
+            let inputs: Vec<&(Vec<usize>, Vec<f64>)> = strings(node, 1).iter().map(|name| &values[name]).collect();
+            let output = match strings(node, 4)[0].as_str() {
+                "MatMul" => {
+                    let (dims, weights) = inputs[1];
+                    let row = &inputs[0].1;
+                    let data = (0..dims[1]).map(|column| (0..dims[0]).map(|r| row[r] * weights[r * dims[1] + column]).sum()).collect();
+                    (vec![dims[1]], data)
+                },
+                "Sigmoid" => (inputs[0].0.clone(), inputs[0].1.iter().map(|x| 1.0 / (1.0 + (-x).exp())).collect()),
+                "Concat" => {
+                    let data: Vec<f64> = inputs[0].1.iter().chain(inputs[1].1.iter()).copied().collect();
+                    (vec![data.len()], data)
+                },
+                "Gather" => {
+                    let data: Vec<f64> = inputs[1].1.iter().map(|&index| inputs[0].1[index as usize]).collect();
+                    (vec![data.len()], data)
+                },
+                op_type => panic!("unexpected op {}", op_type),
+            };
+            values.insert(strings(node, 2)[0].clone(), output);
+        }
+        values["output"].1.clone()
+    }
+
+    #[test]
+    fn test_model_structure() {
+        let network = FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        let model = network.to_onnx();
+
+        assert_eq!(varints(&model, 1), vec![IR_VERSION]);
+        assert_eq!(varints(submessages(&model, 8)[0], 2), vec![OPSET_VERSION]);
+        let graph = submessages(&model, 7)[0];
+        let ops: Vec<String> = submessages(graph, 1).iter().map(|node| strings(node, 4)[0].clone()).collect();
+        assert_eq!(ops, vec!["MatMul", "Sigmoid", "Concat", "MatMul", "Sigmoid", "Concat", "Gather"]);
+        assert_eq!(strings(submessages(graph, 11)[0], 1), vec!["input"]);
+        assert_eq!(strings(submessages(graph, 12)[0], 1), vec!["output"]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut network = FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        let model = network.to_onnx();
+
+        for input in [[0.0, 0.0], [1.0, -1.0], [0.25, 3.5], [-2.0, 0.125]].iter() {
+            let expected = network.activate(input);
+            let output = run(&model, input);
+            assert_eq!(output.len(), expected.len());
+            // The model stores its weights as f32.
+            assert!(output.iter().zip(expected.iter()).all(|(a, b)| (a - b).abs() < 1e-6));
+        }
+    }
+}
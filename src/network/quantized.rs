@@ -0,0 +1,263 @@
+use std::convert::TryFrom;
+use super::Csr;
+use super::compiled::{ CompiledNetwork, Scalar };
+use super::feed_forward::FeedForwardNetwork;
+use super::recurrent::RecurrentNetwork;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Precision {
+    Int8,
+    Int16,
+}
+
+impl Precision {
+    pub fn get_max(self) -> i32 {
+        match self {
+            Precision::Int8 => i32::from(i8::MAX),
+            Precision::Int16 => i32::from(i16::MAX),
+        }
+    }
+
+    // Neuron values are fixed point with one sign bit, so 1.0 is just out of range
+    // and saturates to `get_max`.
+    pub fn get_fraction_bits(self) -> u32 {
+        match self {
+            Precision::Int8 => 7,
+            Precision::Int16 => 15,
+        }
+    }
+}
+
+// Integer-only evaluation of a phenotype. Weights share one power-of-two scale,
+// `weight = stored / 2^weight_shift`, so rescaling the accumulator is a shift and
+// the activations are the piecewise-linear approximations below.
+#[derive(Clone, Debug)]
+pub struct QuantizedNetwork {
+    precision: Precision,
+    weight_shift: i32,
+    csr: Csr<i16>,
+    recurrent: bool,
+    values: Vec<i32>,
+    next: Vec<i32>,
+}
+
+impl FeedForwardNetwork {
+    pub fn quantize(&self, precision: Precision) -> QuantizedNetwork {
+        QuantizedNetwork::new(&Csr::new(self.get_topology()), precision, false)
+    }
+}
+
+impl RecurrentNetwork {
+    pub fn quantize(&self, precision: Precision) -> QuantizedNetwork {
+        QuantizedNetwork::new(&Csr::new(self.get_topology()), precision, true)
+    }
+}
+
+impl<T: Scalar> CompiledNetwork<T> {
+    pub fn quantize(&self, precision: Precision) -> QuantizedNetwork {
+        QuantizedNetwork::new(self.get_csr(), precision, false)
+    }
+}
+
+impl QuantizedNetwork {
+    fn new<T: Scalar>(csr: &Csr<T>, precision: Precision, recurrent: bool) -> QuantizedNetwork {
+        let max_weight = csr.weights.iter()
+            .map(|weight| weight.to_f64().abs())
+            .filter(|weight| weight.is_finite())
+            .fold(0.0, f64::max);
+        // The largest shift that keeps every weight within the precision. Huge weights
+        // saturate instead of pushing the shift further down, since any shift below
+        // -32 already saturates every accumulator that is not zero.
+        let weight_shift = if max_weight > 0.0 {
+            (f64::from(precision.get_max()) / max_weight).log2().floor().clamp(-32.0, 30.0) as i32
+        } else {
+            0
+        };
+
+        let max = f64::from(precision.get_max());
+        let csr = csr.map(|weight| (weight.to_f64() * 2f64.powi(weight_shift)).round().max(-max).min(max) as i16);
+        let n_values = csr.get_n_values();
+        QuantizedNetwork {
+            precision,
+            weight_shift,
+            csr,
+            recurrent,
+            values: vec![0; n_values],
+            next: vec![0; n_values],
+        }
+    }
+
+    pub fn get_precision(&self) -> Precision {
+        self.precision
+    }
+
+    pub fn get_weight_shift(&self) -> i32 {
+        self.weight_shift
+    }
+
+    pub fn get_weights(&self) -> &[i16] {
+        &self.csr.weights
+    }
+
+    pub fn reset(&mut self) {
+        self.values.iter_mut().for_each(|value| *value = 0);
+    }
+
+    // Inputs and outputs are fixed point with `get_fraction_bits` fractional bits.
+    pub fn activate_fixed(&mut self, inputs: &[i32]) -> Vec<i32> {
+        let n_inputs = self.csr.n_inputs;
+        assert_eq!(inputs.len(), n_inputs, "expected {} inputs, got {}", n_inputs, inputs.len());
+        self.values[..n_inputs].copy_from_slice(inputs);
+
+        let bits = self.precision.get_fraction_bits();
+        for (node, (sources, weights)) in self.csr.iter_nodes().enumerate() {
+            let sum: i64 = sources.iter()
+                .zip(weights.iter())
+                .map(|(&source, &weight)| i64::from(weight) * i64::from(self.values[source as usize]))
+                .sum();
+            let value = sigmoid(saturate(shift(sum, self.weight_shift)), bits).min(self.precision.get_max());
+            if self.recurrent {
+                self.next[n_inputs + node] = value;
+            } else {
+                self.values[n_inputs + node] = value;
+            }
+        }
+        if self.recurrent {
+            self.values[n_inputs..].copy_from_slice(&self.next[n_inputs..]);
+        }
+
+        self.csr.outputs.iter().map(|&index| self.values[index as usize]).collect()
+    }
+
+    pub fn activate(&mut self, inputs: &[f64]) -> Vec<f64> {
+        let scale = 2f64.powi(self.precision.get_fraction_bits() as i32);
+        let inputs: Vec<i32> = inputs.iter()
+            .map(|input| (input * scale).round().max(f64::from(i32::MIN)).min(f64::from(i32::MAX)) as i32)
+            .collect();
+        self.activate_fixed(&inputs).into_iter().map(|value| f64::from(value) / scale).collect()
+    }
+
+    // Largest absolute difference from the reference phenotype over the samples.
+    // Both start from a reset state and see the samples in order, which also makes
+    // the comparison meaningful for recurrent networks.
+    pub fn max_error<F>(&mut self, inputs: &[Vec<f64>], mut reference: F) -> f64
+    where
+        F: FnMut(&[f64]) -> Vec<f64>
+    {
+        self.reset();
+        let mut max_error: f64 = 0.0;
+        for input in inputs {
+            let expected = reference(input);
+            for (value, expected) in self.activate(input).iter().zip(expected.iter()) {
+                max_error = max_error.max((value - expected).abs());
+            }
+        }
+        max_error
+    }
+}
+
+fn shift(value: i64, shift: i32) -> i64 {
+    match shift {
+        0 => value,
+        shift if shift > 0 => (value + (1 << (shift - 1))) >> shift,
+        shift => value.saturating_mul(1 << -shift),
+    }
+}
+
+fn saturate(value: i64) -> i32 {
+    i32::try_from(value).unwrap_or(if value < 0 { i32::MIN } else { i32::MAX })
+}
+
+// PLAN approximation: every segment has a power-of-two slope, so no multiplier is needed.
+pub fn sigmoid(x: i32, fraction_bits: u32) -> i32 {
+    let one = 1i64 << fraction_bits;
+    let a = i64::from(x).abs();
+    let y = if a >= 5 * one {
+        one
+    } else if a >= 19 * one / 8 {
+        (a >> 5) + 27 * one / 32
+    } else if a >= one {
+        (a >> 3) + 5 * one / 8
+    } else {
+        (a >> 2) + one / 2
+    };
+    (if x < 0 { one - y } else { y }) as i32
+}
+
+// tanh(x) = 2 sigmoid(2x) - 1, on the same piecewise-linear sigmoid.
+pub fn tanh(x: i32, fraction_bits: u32) -> i32 {
+    let one = 1i32 << fraction_bits;
+    2 * sigmoid(x.saturating_mul(2), fraction_bits) - one
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{ Rng, SeedableRng };
+    use crate::utils::NeatRng;
+    use crate::network::tests::{ genome, FEED_FORWARD, RECURRENT };
+
+    fn samples(n_inputs: usize) -> Vec<Vec<f64>> {
+        let mut rng = NeatRng::seed_from_u64(3);
+        (0..200).map(|_| (0..n_inputs).map(|_| rng.gen_range(-1.0, 1.0)).collect()).collect()
+    }
+
+    #[test]
+    fn test_activations() {
+        for &x in [-6.0f64, -3.0, -1.5, -0.5, 0.0, 0.25, 1.0, 2.0, 4.0, 8.0].iter() {
+            let fixed = (x * 32768.0) as i32;
+            let sigmoid = f64::from(sigmoid(fixed, 15)) / 32768.0;
+            let tanh = f64::from(tanh(fixed, 15)) / 32768.0;
+            assert!((sigmoid - 1.0 / (1.0 + (-x).exp())).abs() < 0.02, "sigmoid({})", x);
+            assert!((tanh - x.tanh()).abs() < 0.04, "tanh({})", x);
+        }
+        assert_eq!(sigmoid(0, 7), 64);
+        assert_eq!(sigmoid(i32::MIN, 7), 0);
+    }
+
+    #[test]
+    fn test_quantize() {
+        let mut network = FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        let inputs = samples(2);
+
+        let mut int8 = network.quantize(Precision::Int8);
+        // The largest weight is 3.0, so 3.0 * 2^5 = 96 is the last multiple that fits.
+        assert_eq!(int8.get_weight_shift(), 5);
+        assert_eq!(int8.get_weights(), &[48, -64, 96, -24]);
+        let int8_error = int8.max_error(&inputs, |input| network.activate(input));
+
+        let mut int16 = network.quantize(Precision::Int16);
+        let int16_error = int16.max_error(&inputs, |input| network.activate(input));
+
+        assert!(int16_error < 0.03, "int16 error {}", int16_error);
+        assert!(int8_error < 0.05, "int8 error {}", int8_error);
+
+        let mut compiled = network.compile::<f32>().quantize(Precision::Int16);
+        assert_eq!(compiled.get_weights(), int16.get_weights());
+        assert_eq!(compiled.max_error(&inputs, |input| int16.activate(input)), 0.0);
+    }
+
+    #[test]
+    fn test_quantize_huge_weights() {
+        let network = FeedForwardNetwork::new(&genome("
+            neuron 0 sensor
+            neuron 1 output
+            connection 2 0 1 1e30 enabled
+        ")).unwrap();
+        let mut int8 = network.quantize(Precision::Int8);
+        assert_eq!(int8.get_weight_shift(), -32);
+        assert_eq!(int8.get_weights(), &[127]);
+        assert_eq!(int8.activate(&[1.0]), vec![127.0 / 128.0]);
+        assert_eq!(int8.activate(&[-1.0]), vec![0.0]);
+        assert_eq!(shift(i64::MAX / 3, -32), i64::MAX);
+        assert_eq!(shift(i64::MIN / 3, -32), i64::MIN);
+    }
+
+    #[test]
+    fn test_quantize_recurrent() {
+        let mut network = RecurrentNetwork::new(&genome(RECURRENT)).unwrap();
+        let mut quantized = network.quantize(Precision::Int16);
+        let error = quantized.max_error(&samples(1), |input| network.activate(input));
+        assert!(error < 0.05, "recurrent error {}", error);
+    }
+}
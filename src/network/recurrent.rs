@@ -0,0 +1,133 @@
+use crate::genome::{ Genome, Gene };
+use crate::genome::neuron_gene;
+use crate::NeatError;
+use super::{ Topology, activate_row, sigmoid };
+
+// Every neuron is updated once per `activate` from the values of the previous
+// step, so links may form cycles and a signal needs one step per hop.
+#[derive(Clone, Debug)]
+pub struct RecurrentNetwork {
+    topology: Topology,
+    values: Vec<f64>,
+    next: Vec<f64>,
+}
+
+impl RecurrentNetwork {
+    pub fn new(genome: &Genome) -> Result<RecurrentNetwork, NeatError> {
+        let order: Vec<u32> = genome.iter_neurons()
+            .filter(|neuron| neuron.get_class() != neuron_gene::SENSOR)
+            .map(|neuron| neuron.get_innovation_number())
+            .collect();
+        let topology = Topology::new(genome, &order)?;
+        let values = vec![0.0; topology.get_n_values()];
+
+        Ok(RecurrentNetwork {
+            topology,
+            next: values.clone(),
+            values,
+        })
+    }
+
+    pub fn get_n_inputs(&self) -> usize {
+        self.topology.n_inputs
+    }
+
+    pub fn get_n_outputs(&self) -> usize {
+        self.topology.outputs.len()
+    }
+
+    pub(crate) fn get_topology(&self) -> &Topology {
+        &self.topology
+    }
+
+    pub fn reset(&mut self) {
+        self.values.iter_mut().for_each(|value| *value = 0.0);
+    }
+
+    pub fn activate(&mut self, inputs: &[f64]) -> Vec<f64> {
+        self.topology.check_inputs(inputs);
+        let n_inputs = self.topology.n_inputs;
+        self.values[..n_inputs].copy_from_slice(inputs);
+
+        for (position, node) in self.topology.nodes.iter().enumerate() {
+            let sum: f64 = node.links.iter().map(|&(source, weight)| weight * self.values[source]).sum();
+            self.next[n_inputs + position] = sigmoid(sum);
+        }
+        self.values[n_inputs..].copy_from_slice(&self.next[n_inputs..]);
+
+        self.topology.outputs.iter().map(|&index| self.values[index]).collect()
+    }
+
+    // Runs equally long sequences in lockstep, each starting from a reset state,
+    // and returns the outputs of every step. The network's own state is left as is.
+    pub fn activate_batch<S, R>(&mut self, sequences: &[S]) -> Vec<Vec<Vec<f64>>>
+    where
+        S: AsRef<[R]>,
+        R: AsRef<[f64]>
+    {
+        let batch = sequences.len();
+        let steps = sequences.first().map_or(0, |sequence| sequence.as_ref().len());
+        assert!(
+            sequences.iter().all(|sequence| sequence.as_ref().len() == steps),
+            "every sequence in a batch must have the same length"
+        );
+
+        let n_inputs = self.topology.n_inputs;
+        let mut values = vec![0.0; self.topology.get_n_values() * batch];
+        let mut next = values.clone();
+        let mut outputs = vec![Vec::with_capacity(steps); batch];
+        for step in 0..steps {
+            let rows = sequences.iter().map(|sequence| sequence.as_ref()[step].as_ref());
+            self.topology.write_inputs(rows, batch, &mut values);
+            for (position, node) in self.topology.nodes.iter().enumerate() {
+                let start = (n_inputs + position) * batch;
+                activate_row(node, &values, batch, &mut next[start..start + batch]);
+            }
+            values[n_inputs * batch..].copy_from_slice(&next[n_inputs * batch..]);
+
+            for (sample, output) in outputs.iter_mut().enumerate() {
+                output.push(self.topology.read_outputs(batch, sample, &values).collect());
+            }
+        }
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::tests::{ genome, RECURRENT };
+
+    #[test]
+    fn test_activate() {
+        let mut network = RecurrentNetwork::new(&genome(RECURRENT)).unwrap();
+        assert_eq!(network.get_n_inputs(), 1);
+
+        // Every neuron starts at zero, so the output only sees the input after two hops.
+        let first = network.activate(&[1.0]);
+        assert_eq!(first, vec![sigmoid(0.0)]);
+        let (h2, h3) = (sigmoid(1.25), sigmoid(0.0));
+
+        assert_eq!(network.activate(&[1.0]), vec![sigmoid(h3)]);
+        assert_eq!(network.activate(&[1.0]), vec![sigmoid(sigmoid(-0.5 * h2))]);
+
+        network.reset();
+        assert_eq!(network.activate(&[1.0]), first);
+    }
+
+    #[test]
+    fn test_activate_batch() {
+        let mut network = RecurrentNetwork::new(&genome(RECURRENT)).unwrap();
+        let sequences = vec![
+            vec![vec![1.0], vec![0.5], vec![-1.0], vec![0.0]],
+            vec![vec![-2.0], vec![0.25], vec![3.0], vec![1.0]],
+        ];
+
+        let outputs = network.activate_batch(&sequences);
+        for (sequence, outputs) in sequences.iter().zip(outputs.iter()) {
+            network.reset();
+            let expected: Vec<Vec<f64>> = sequence.iter().map(|input| network.activate(input)).collect();
+            assert_eq!(outputs, &expected);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use super::{ Topology, check_identifier, weighted_sum, RUST_KEYWORDS };
+use super::feed_forward::FeedForwardNetwork;
+use super::recurrent::RecurrentNetwork;
+use crate::NeatError;
+
+// The generated code only needs `std` for `f64::exp` and has no dependency on this crate.
+impl FeedForwardNetwork {
+    pub fn to_rust(&self, function_name: &str) -> Result<String, NeatError> {
+        check_identifier(function_name, RUST_KEYWORDS)?;
+        let topology = self.get_topology();
+        let operand = |index: usize| value_name(topology, index);
+
+        let mut code = header("feed-forward", topology);
+        code.push_str("#[allow(unused_variables, clippy::excessive_precision, clippy::unreadable_literal)]\n");
+        code.push_str(&format!(
+            "pub fn {}(input: &[f64; {}]) -> [f64; {}] {{\n",
+            function_name, topology.n_inputs, topology.outputs.len()
+        ));
+        code.push_str(SIGMOID);
+        for node in topology.nodes.iter() {
+            code.push_str(&format!(
+                "    let n{} = sigmoid({});\n",
+                node.neuron, weighted_sum(&node.links, rust_literal, operand)
+            ));
+        }
+        let outputs: Vec<String> = topology.outputs.iter().map(|&index| operand(index)).collect();
+        code.push_str(&format!("    [{}]\n}}\n", outputs.join(", ")));
+        Ok(code)
+    }
+}
+
+impl RecurrentNetwork {
+    pub fn to_rust(&self, struct_name: &str) -> Result<String, NeatError> {
+        check_identifier(struct_name, RUST_KEYWORDS)?;
+        let topology = self.get_topology();
+        let n_state = topology.nodes.len();
+        let operand = |index: usize| if index < topology.n_inputs {
+            format!("input[{}]", index)
+        } else {
+            format!("self.state[{}]", index - topology.n_inputs)
+        };
+
+        let mut code = header("recurrent", topology);
+        code.push_str(&format!("#[derive(Clone, Debug)]\npub struct {} {{\n    state: [f64; {}],\n}}\n\n", struct_name, n_state));
+        code.push_str(&format!("impl {} {{\n", struct_name));
+        code.push_str(&format!(
+            "    pub fn new() -> {name} {{\n        {name} {{ state: [0.0; {n}] }}\n    }}\n\n",
+            name = struct_name, n = n_state
+        ));
+        code.push_str(&format!("    pub fn reset(&mut self) {{\n        self.state = [0.0; {}];\n    }}\n\n", n_state));
+        code.push_str("    #[allow(clippy::excessive_precision, clippy::unreadable_literal)]\n");
+        code.push_str(&format!(
+            "    pub fn activate(&mut self, input: &[f64; {}]) -> [f64; {}] {{\n",
+            topology.n_inputs, topology.outputs.len()
+        ));
+        for line in SIGMOID.lines() {
+            if !line.is_empty() {
+                code.push_str("    ");
+            }
+            code.push_str(line);
+            code.push('\n');
+        }
+        code.push_str("        let next = [\n");
+        for node in topology.nodes.iter() {
+            code.push_str(&format!(
+                "            sigmoid({}), // n{}\n",
+                weighted_sum(&node.links, rust_literal, operand), node.neuron
+            ));
+        }
+        code.push_str("        ];\n        self.state = next;\n");
+        let outputs: Vec<String> = topology.outputs.iter().map(|&index| operand(index)).collect();
+        code.push_str(&format!("        [{}]\n    }}\n}}\n\n", outputs.join(", ")));
+        code.push_str(&format!(
+            "impl Default for {name} {{\n    fn default() -> {name} {{\n        {name}::new()\n    }}\n}}\n",
+            name = struct_name
+        ));
+        Ok(code)
+    }
+}
+
+const SIGMOID: &str = "    fn sigmoid(x: f64) -> f64 {
+        1.0 / (1.0 + (-x).exp())
+    }
+
+";
+
+fn header(kind: &str, topology: &Topology) -> String {
+    format!(
+        "// Generated by rust_neat from a {} network with {} inputs and {} outputs.\n",
+        kind, topology.n_inputs, topology.outputs.len()
+    )
+}
+
+fn value_name(topology: &Topology, index: usize) -> String {
+    if index < topology.n_inputs {
+        format!("input[{}]", index)
+    } else {
+        format!("n{}", topology.nodes[index - topology.n_inputs].neuron)
+    }
+}
+
+// Debug formatting prints the shortest representation that reads back as the same f64.
+fn rust_literal(value: f64) -> String {
+    if value.is_nan() {
+        "f64::NAN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "f64::INFINITY" } else { "f64::NEG_INFINITY" }.to_string()
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::process::Command;
+    use crate::network::tests::{ genome, FEED_FORWARD, RECURRENT };
+
+    const INPUTS: [[f64; 2]; 4] = [[0.0, 0.0], [1.0, -1.0], [0.25, 3.5], [-2.0, 0.125]];
+
+    #[test]
+    fn test_feed_forward_source() {
+        let network = FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        let code = network.to_rust("controller").unwrap();
+        assert!(code.contains("pub fn controller(input: &[f64; 2]) -> [f64; 1] {"));
+        assert!(code.contains("    let n3 = sigmoid(1.5 * input[0] - 2.0 * input[1]);\n"));
+        assert!(code.contains("    let n2 = sigmoid(3.0 * n3 - 0.75 * input[0]);\n"));
+        assert!(!code.contains("4.0"));
+
+        assert!(matches!(network.to_rust("fn"), Err(NeatError::InvalidIdentifier(_))));
+        assert!(matches!(network.to_rust("2d"), Err(NeatError::InvalidIdentifier(_))));
+    }
+
+    // Compiles the generated code with rustc and checks it against the phenotypes.
+    #[test]
+    fn test_generated_code_matches() {
+        let mut feed_forward = FeedForwardNetwork::new(&genome(FEED_FORWARD)).unwrap();
+        let mut recurrent = RecurrentNetwork::new(&genome(RECURRENT)).unwrap();
+
+        let mut program = feed_forward.to_rust("feed_forward").unwrap();
+        program.push_str(&recurrent.to_rust("Recurrent").unwrap());
+        program.push_str("\nfn main() {\n    let mut recurrent = Recurrent::new();\n");
+        for input in INPUTS.iter() {
+            program.push_str(&format!("    println!(\"{{:?}}\", feed_forward(&{:?}));\n", input));
+            program.push_str(&format!("    println!(\"{{:?}}\", recurrent.activate(&[{:?}]));\n", input[0]));
+        }
+        program.push_str("}\n");
+
+        let directory = env::temp_dir().join(format!("rust_neat_codegen_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let source = directory.join("main.rs");
+        let binary = directory.join("generated");
+        fs::write(&source, &program).unwrap();
+
+        let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+        let status = Command::new(rustc)
+            .args(["--edition", "2018", "-o"])
+            .arg(&binary)
+            .arg(&source)
+            .status()
+            .unwrap();
+        assert!(status.success(), "generated code failed to compile:\n{}", program);
+
+        let output = Command::new(&binary).output().unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let mut lines = stdout.lines();
+        for input in INPUTS.iter() {
+            assert_eq!(lines.next().unwrap(), format!("{:?}", feed_forward.activate(input)));
+            assert_eq!(lines.next().unwrap(), format!("{:?}", recurrent.activate(&input[..1])));
+        }
+    }
+}
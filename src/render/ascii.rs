@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use crate::genome::Genome;
+use super::Layout;
+
+// Beyond this size the lane diagram gets too wide to read, so the adjacency table is used.
+const MAX_DIAGRAM_NEURONS: usize = 24;
+const LANE_NAMES: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+impl Genome {
+    pub fn render_ascii(&self) -> String {
+        let n_connections = self.iter_connections().count();
+        if self.iter_neurons().count() > MAX_DIAGRAM_NEURONS || n_connections > LANE_NAMES.len() {
+            return self.render_ascii_table();
+        }
+
+        let layout = Layout::new(self);
+        let mut out = render_columns(&layout);
+        out.push('\n');
+
+        // One row per neuron in layer order; every connection gets its own vertical lane
+        // on the right and enters its target through the `<` next to the neuron.
+        let order: Vec<u32> = layout.get_layers().iter().flatten().copied().collect();
+        let row: HashMap<u32, usize> = order.iter().enumerate().map(|(index, &id)| (id, index)).collect();
+        let label_width = order.iter().map(|&id| layout.get_label(id).len()).max().unwrap_or(0);
+        let mut grid = vec![vec![' '; 2 + 2 * n_connections]; order.len()];
+
+        for (lane, connection) in self.iter_connections().enumerate() {
+            let (source, target) = match (row.get(&connection.get_neuron_in()), row.get(&connection.get_neuron_out())) {
+                (Some(&source), Some(&target)) => (source, target),
+                _ => continue
+            };
+            let x = 2 + 2 * lane;
+            let (vertical, horizontal) = if connection.is_enabled() { ('|', '-') } else { (':', '.') };
+
+            for line in grid.iter_mut().take(source.max(target)).skip(source.min(target) + 1) {
+                draw(&mut line[x], vertical);
+            }
+            for &index in &[source, target] {
+                for cell in grid[index][1..x].iter_mut() {
+                    draw(cell, horizontal);
+                }
+            }
+            if grid[source][0] == ' ' {
+                grid[source][0] = horizontal;
+            }
+            grid[target][0] = '<';
+            grid[source][x] = if source == target { '@' } else { '+' };
+            grid[target][x] = grid[source][x];
+        }
+
+        for (index, &id) in order.iter().enumerate() {
+            let lanes: String = grid[index].iter().collect();
+            out.push_str(format!("{:>width$} *{}", layout.get_label(id), lanes, width = label_width).trim_end());
+            out.push('\n');
+        }
+
+        out.push('\n');
+        for (lane, connection) in self.iter_connections().enumerate() {
+            out.push_str(&format!(
+                "{} {} -> {} {:+.3}{}\n",
+                LANE_NAMES[lane] as char,
+                layout.get_label(connection.get_neuron_in()),
+                layout.get_label(connection.get_neuron_out()),
+                connection.get_weight(),
+                if connection.is_enabled() { "" } else { " (disabled)" }
+            ));
+        }
+        out
+    }
+
+    pub fn render_ascii_table(&self) -> String {
+        let layout = Layout::new(self);
+        let order: Vec<u32> = layout.get_layers().iter().flatten().copied().collect();
+        let sources: Vec<u32> = order.iter().copied()
+            .filter(|&id| self.iter_connections().any(|connection| connection.get_neuron_in() == id))
+            .collect();
+        let targets: Vec<u32> = order.iter().copied()
+            .filter(|&id| self.iter_connections().any(|connection| connection.get_neuron_out() == id))
+            .collect();
+
+        let mut cells: HashMap<(u32, u32), String> = HashMap::new();
+        for connection in self.iter_connections() {
+            let weight = format!("{:+.3}", connection.get_weight());
+            let cell = if connection.is_enabled() { weight } else { format!("({})", weight) };
+            cells.insert((connection.get_neuron_in(), connection.get_neuron_out()), cell);
+        }
+
+        let label_width = sources.iter().map(|&id| layout.get_label(id).len()).max().unwrap_or(0).max("from\\to".len());
+        let cell_width = cells.values().map(String::len)
+            .chain(targets.iter().map(|&id| layout.get_label(id).len()))
+            .max()
+            .unwrap_or(1);
+
+        let mut out = format!("{:<width$}", "from\\to", width = label_width);
+        for &target in targets.iter() {
+            out.push_str(&format!(" {:>width$}", layout.get_label(target), width = cell_width));
+        }
+        out.push('\n');
+        for &source in sources.iter() {
+            let mut line = format!("{:<width$}", layout.get_label(source), width = label_width);
+            for &target in targets.iter() {
+                let cell = cells.get(&(source, target)).map_or(".", String::as_str);
+                line.push_str(&format!(" {:>width$}", cell, width = cell_width));
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+// Overlapping runs keep the enabled style; a horizontal run over a lane is a crossing.
+fn draw(cell: &mut char, line: char) {
+    *cell = match (*cell, line) {
+        (' ', _) => line,
+        ('-', '.') | ('.', '-') => '-',
+        ('|', ':') | (':', '|') => '|',
+        (existing, _) if existing == line => line,
+        _ => '+',
+    };
+}
+
+fn render_columns(layout: &Layout) -> String {
+    let layers = layout.get_layers();
+    let widths: Vec<usize> = layers.iter()
+        .map(|layer| layer.iter().map(|&id| layout.get_label(id).len()).max().unwrap_or(0))
+        .collect();
+    let rows = layers.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for row in 0..rows {
+        let mut line = String::new();
+        for (layer, width) in layers.iter().zip(widths.iter()) {
+            let label = layer.get(row).map_or("", |&id| layout.get_label(id));
+            line.push_str(&format!("{:<width$}   ", label, width = width));
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ GenomeConfig, InnovationCounter };
+
+    const GENOME: &str = "
+        neuron 0 sensor
+        neuron 1 sensor
+        neuron 2 output
+        neuron 3 hidden 100
+        connection 4 0 3 2 enabled
+        connection 5 3 2 -1 enabled
+        connection 6 1 2 0.5 disabled
+    ";
+
+    #[test]
+    fn test_render_ascii() {
+        let genome: Genome = GENOME.parse().unwrap();
+        let expected = "\
+in0   h3   out0
+in1
+
+ in0 *--+
+ in1 *..+...+
+  h3 *<-+-+ :
+out0 *<---+.+
+
+a in0 -> h3 +2.000
+b h3 -> out0 -1.000
+c in1 -> out0 +0.500 (disabled)
+";
+        assert_eq!(genome.render_ascii(), expected);
+    }
+
+    #[test]
+    fn test_render_ascii_table() {
+        let genome: Genome = GENOME.parse().unwrap();
+        let expected = "\
+from\\to       h3     out0
+in0       +2.000        .
+in1            . (+0.500)
+h3             .   -1.000
+";
+        assert_eq!(genome.render_ascii_table(), expected);
+
+        let mut config = GenomeConfig::new(20, 10);
+        config.set_is_connected(true);
+        let large = Genome::new(&mut InnovationCounter::new(30), &config).unwrap();
+        assert!(large.render_ascii().starts_with("from\\to"));
+    }
+}
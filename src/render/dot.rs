@@ -0,0 +1,173 @@
+use std::fmt;
+use crate::genome::{ Genome, Gene };
+use super::{ Layout, Role, activation_name };
+
+#[derive(Clone, Debug)]
+pub struct DotOptions {
+    show_innovations: bool,
+    show_weights: bool,
+    show_activations: bool,
+    show_disabled: bool,
+}
+
+impl DotOptions {
+    pub fn new() -> DotOptions {
+        DotOptions {
+            show_innovations: false,
+            show_weights: true,
+            show_activations: false,
+            show_disabled: true,
+        }
+    }
+
+    pub fn set_show_innovations(&mut self, show_innovations: bool) {
+        self.show_innovations = show_innovations;
+    }
+
+    pub fn set_show_weights(&mut self, show_weights: bool) {
+        self.show_weights = show_weights;
+    }
+
+    pub fn set_show_activations(&mut self, show_activations: bool) {
+        self.show_activations = show_activations;
+    }
+
+    pub fn set_show_disabled(&mut self, show_disabled: bool) {
+        self.show_disabled = show_disabled;
+    }
+}
+
+impl Default for DotOptions {
+    fn default() -> DotOptions {
+        DotOptions::new()
+    }
+}
+
+impl Genome {
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotOptions::new())
+    }
+
+    pub fn to_dot_with(&self, options: &DotOptions) -> String {
+        let mut dot = String::new();
+        self.write_dot(&mut dot, options).expect("writing to a String cannot fail");
+        dot
+    }
+
+    pub fn write_dot<W: fmt::Write>(&self, writer: &mut W, options: &DotOptions) -> fmt::Result {
+        let layout = Layout::new(self);
+        let max_weight = self.iter_connections()
+            .map(|connection| connection.get_weight().abs())
+            .fold(0.0, f64::max);
+
+        writeln!(writer, "digraph genome {{")?;
+        writeln!(writer, "    rankdir=LR;")?;
+        writeln!(writer, "    splines=true;")?;
+        writeln!(writer, "    node [fontname=\"Helvetica\", style=filled];")?;
+
+        for (index, layer) in layout.get_layers().iter().enumerate() {
+            writeln!(writer, "    subgraph layer_{} {{", index)?;
+            writeln!(writer, "        rank=same;")?;
+            for &id in layer {
+                let neuron = match self.get_neuron(id) {
+                    Some(neuron) => neuron,
+                    None => continue
+                };
+                let mut label = layout.get_label(id).to_string();
+                if options.show_innovations {
+                    label.push_str(&format!("\\n#{}", id));
+                }
+                if options.show_activations && Role::of(neuron) != Role::Sensor {
+                    label.push_str(&format!("\\n{}", activation_name(neuron)));
+                }
+                let (shape, color) = match Role::of(neuron) {
+                    Role::Sensor => ("box", "#dceefb"),
+                    Role::Hidden => ("circle", "#eeeeee"),
+                    Role::Output => ("doublecircle", "#fde2c8"),
+                };
+                writeln!(writer, "        n{} [label=\"{}\", shape={}, fillcolor=\"{}\"];", id, label, shape, color)?;
+            }
+            writeln!(writer, "    }}")?;
+        }
+
+        for connection in self.iter_connections() {
+            if !connection.is_enabled() && !options.show_disabled {
+                continue;
+            }
+            let weight = connection.get_weight();
+            let magnitude = if max_weight > 0.0 { weight.abs() / max_weight } else { 0.0 };
+            let color = if weight >= 0.0 { "#2b83ba" } else { "#d7191c" };
+
+            let mut label = Vec::new();
+            if options.show_innovations {
+                label.push(format!("#{}", connection.get_innovation_number()));
+            }
+            if options.show_weights {
+                label.push(format!("{:.3}", weight));
+            }
+
+            write!(
+                writer,
+                "    n{} -> n{} [color=\"{}\", penwidth={:.2}",
+                connection.get_neuron_in(), connection.get_neuron_out(), color, 0.5 + 3.5 * magnitude
+            )?;
+            if !label.is_empty() {
+                write!(writer, ", label=\"{}\"", label.join(" "))?;
+            }
+            if !connection.is_enabled() {
+                write!(writer, ", style=dashed")?;
+            }
+            writeln!(writer, "];")?;
+        }
+
+        writeln!(writer, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENOME: &str = "
+        neuron 0 sensor
+        neuron 1 output
+        neuron 2 hidden 100
+        connection 3 0 2 2 enabled
+        connection 4 2 1 -0.5 enabled
+        connection 5 0 1 1 disabled
+    ";
+
+    #[test]
+    fn test_to_dot() {
+        let genome: Genome = GENOME.parse().unwrap();
+        let dot = genome.to_dot();
+
+        assert!(dot.starts_with("digraph genome {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("n0 [label=\"in0\", shape=box"));
+        assert!(dot.contains("n1 [label=\"out0\", shape=doublecircle"));
+        assert!(dot.contains("n0 -> n2 [color=\"#2b83ba\", penwidth=4.00, label=\"2.000\"];"));
+        assert!(dot.contains("n2 -> n1 [color=\"#d7191c\", penwidth=1.38, label=\"-0.500\"];"));
+        assert!(dot.contains("n0 -> n1 [color=\"#2b83ba\", penwidth=2.25, label=\"1.000\", style=dashed];"));
+
+        let layer_0 = dot.find("subgraph layer_0").unwrap();
+        let layer_2 = dot.find("subgraph layer_2").unwrap();
+        assert!(layer_0 < dot.find("n0 [").unwrap() && dot.find("n1 [").unwrap() > layer_2);
+    }
+
+    #[test]
+    fn test_dot_options() {
+        let genome: Genome = GENOME.parse().unwrap();
+        let mut options = DotOptions::new();
+        options.set_show_innovations(true);
+        options.set_show_weights(false);
+        options.set_show_activations(true);
+        options.set_show_disabled(false);
+        let dot = genome.to_dot_with(&options);
+
+        assert!(dot.contains("label=\"h2\\n#2\\nsigmoid\""));
+        assert!(dot.contains("label=\"#3\"]"));
+        assert!(!dot.contains("style=dashed"));
+        assert!(!dot.contains("2.000"));
+    }
+}
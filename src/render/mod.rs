@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use crate::genome::{ Genome, Gene };
+use crate::genome::neuron_gene::{ self, NeuronGene };
+
+pub mod ascii;
+pub mod dot;
+pub mod svg;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Role {
+    Sensor,
+    Hidden,
+    Output,
+}
+
+impl Role {
+    pub(crate) fn of(neuron: &NeuronGene) -> Role {
+        match neuron.get_class() {
+            neuron_gene::SENSOR => Role::Sensor,
+            neuron_gene::OUTPUT => Role::Output,
+            _ => Role::Hidden,
+        }
+    }
+}
+
+pub(crate) fn activation_name(neuron: &NeuronGene) -> &'static str {
+    match Role::of(neuron) {
+        Role::Sensor => "identity",
+        _ => "sigmoid",
+    }
+}
+
+// Column placement shared by the renderers. Sensors take the first layer and
+// outputs the last; a hidden neuron sits one layer after the deepest neuron
+// of lower class that feeds it. Class order is a topological order for every
+// connection the mutations create, so back edges (recurrent links) are ignored.
+pub(crate) struct Layout {
+    layers: Vec<Vec<u32>>,
+    labels: HashMap<u32, String>,
+}
+
+impl Layout {
+    pub(crate) fn new(genome: &Genome) -> Layout {
+        let mut neurons: Vec<&NeuronGene> = genome.iter_neurons().map(|neuron| &**neuron).collect();
+        neurons.sort_by_key(|neuron| (neuron.get_class(), neuron.get_innovation_number()));
+
+        let mut incoming: HashMap<u32, Vec<u32>> = HashMap::new();
+        for connection in genome.iter_connections() {
+            incoming.entry(connection.get_neuron_out()).or_default().push(connection.get_neuron_in());
+        }
+
+        let mut depth: HashMap<u32, usize> = HashMap::new();
+        let mut max_hidden = 0;
+        for neuron in neurons.iter().filter(|neuron| Role::of(neuron) != Role::Output) {
+            let id = neuron.get_innovation_number();
+            let value = if Role::of(neuron) == Role::Sensor {
+                0
+            } else {
+                let feeding = incoming.get(&id).into_iter().flatten()
+                    .filter(|source| genome.get_neuron(**source).is_some_and(|source| source.get_class() < neuron.get_class()))
+                    .filter_map(|source| depth.get(source))
+                    .max();
+                feeding.map_or(1, |depth| depth + 1)
+            };
+            max_hidden = max_hidden.max(value);
+            depth.insert(id, value);
+        }
+
+        let mut layers = vec![Vec::new(); max_hidden + 2];
+        let mut labels = HashMap::new();
+        let (mut n_sensor, mut n_output) = (0, 0);
+        for neuron in neurons.iter() {
+            let id = neuron.get_innovation_number();
+            let (layer, label) = match Role::of(neuron) {
+                Role::Sensor => {
+                    n_sensor += 1;
+                    (0, format!("in{}", n_sensor - 1))
+                },
+                Role::Output => {
+                    n_output += 1;
+                    (max_hidden + 1, format!("out{}", n_output - 1))
+                },
+                Role::Hidden => (depth[&id], format!("h{}", id)),
+            };
+            layers[layer].push(id);
+            labels.insert(id, label);
+        }
+        layers.retain(|layer| !layer.is_empty());
+
+        Layout { layers, labels }
+    }
+
+    pub(crate) fn get_layers(&self) -> &[Vec<u32>] {
+        &self.layers
+    }
+
+    pub(crate) fn get_label(&self, neuron: u32) -> &str {
+        self.labels.get(&neuron).map_or("?", String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        let genome: Genome = "
+            neuron 0 sensor
+            neuron 1 sensor
+            neuron 2 output
+            neuron 3 hidden 100
+            neuron 4 hidden 200
+            neuron 5 hidden 150
+            connection 6 0 3 1 enabled
+            connection 7 3 4 1 enabled
+            connection 8 4 2 1 enabled
+            connection 9 1 2 1 enabled
+        ".parse().unwrap();
+
+        let layout = Layout::new(&genome);
+        assert_eq!(layout.get_layers(), &[vec![0, 1], vec![3, 5], vec![4], vec![2]]);
+        assert_eq!(layout.get_label(1), "in1");
+        assert_eq!(layout.get_label(2), "out0");
+        assert_eq!(layout.get_label(5), "h5");
+    }
+}
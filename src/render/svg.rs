@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::genome::{ Genome, Gene };
+use super::{ Layout, Role };
+
+const MARGIN: f64 = 60.0;
+const COLUMN_SPACING: f64 = 140.0;
+const ROW_SPACING: f64 = 60.0;
+const RADIUS: f64 = 14.0;
+const LEGEND_HEIGHT: f64 = 70.0;
+
+const POSITIVE: &str = "#2b83ba";
+const NEGATIVE: &str = "#d7191c";
+const DISABLED: &str = "#f39c12";
+
+#[derive(Clone, Debug)]
+pub struct SvgOptions {
+    show_legend: bool,
+    show_labels: bool,
+    highlight_disabled: bool,
+}
+
+impl SvgOptions {
+    pub fn new() -> SvgOptions {
+        SvgOptions {
+            show_legend: true,
+            show_labels: true,
+            highlight_disabled: true,
+        }
+    }
+
+    pub fn set_show_legend(&mut self, show_legend: bool) {
+        self.show_legend = show_legend;
+    }
+
+    pub fn set_show_labels(&mut self, show_labels: bool) {
+        self.show_labels = show_labels;
+    }
+
+    pub fn set_highlight_disabled(&mut self, highlight_disabled: bool) {
+        self.highlight_disabled = highlight_disabled;
+    }
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions::new()
+    }
+}
+
+fn role_color(role: Role) -> &'static str {
+    match role {
+        Role::Sensor => "#dceefb",
+        Role::Hidden => "#eeeeee",
+        Role::Output => "#fde2c8",
+    }
+}
+
+impl Genome {
+    pub fn to_svg(&self) -> String {
+        self.to_svg_with(&SvgOptions::new())
+    }
+
+    pub fn to_svg_with(&self, options: &SvgOptions) -> String {
+        let mut svg = String::new();
+        self.write_svg(&mut svg, options).expect("writing to a String cannot fail");
+        svg
+    }
+
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P, options: &SvgOptions) -> io::Result<()> {
+        fs::write(path, self.to_svg_with(options))
+    }
+
+    pub fn write_svg<W: fmt::Write>(&self, writer: &mut W, options: &SvgOptions) -> fmt::Result {
+        let layout = Layout::new(self);
+        let layers = layout.get_layers();
+        let rows = layers.iter().map(Vec::len).max().unwrap_or(0).max(1);
+
+        let network_height = rows as f64 * ROW_SPACING;
+        let width = 2.0 * MARGIN + (layers.len().max(1) - 1) as f64 * COLUMN_SPACING;
+        let height = 2.0 * MARGIN + network_height + if options.show_legend { LEGEND_HEIGHT } else { 0.0 };
+        let width = width.max(if options.show_legend { 420.0 } else { 0.0 });
+
+        // Each column is centred vertically within the tallest one.
+        let mut position: HashMap<u32, (f64, f64)> = HashMap::new();
+        for (column, layer) in layers.iter().enumerate() {
+            let offset = (rows - layer.len()) as f64 * ROW_SPACING / 2.0;
+            for (row, &id) in layer.iter().enumerate() {
+                let x = MARGIN + column as f64 * COLUMN_SPACING;
+                let y = MARGIN + offset + (row as f64 + 0.5) * ROW_SPACING;
+                position.insert(id, (x, y));
+            }
+        }
+
+        let max_weight = self.iter_connections()
+            .map(|connection| connection.get_weight().abs())
+            .fold(0.0, f64::max);
+
+        writeln!(
+            writer,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = width, h = height
+        )?;
+        writeln!(writer, "<style>text {{ font-family: sans-serif; font-size: 12px; }}</style>")?;
+        writeln!(writer, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>")?;
+
+        writeln!(writer, "<g class=\"connections\" fill=\"none\">")?;
+        for connection in self.iter_connections() {
+            let (from, to) = match (position.get(&connection.get_neuron_in()), position.get(&connection.get_neuron_out())) {
+                (Some(&from), Some(&to)) => (from, to),
+                _ => continue
+            };
+            let weight = connection.get_weight();
+            let magnitude = if max_weight > 0.0 { weight.abs() / max_weight } else { 0.0 };
+            let (color, style) = match (connection.is_enabled(), options.highlight_disabled) {
+                (true, _) => (if weight >= 0.0 { POSITIVE } else { NEGATIVE }, ""),
+                (false, true) => (DISABLED, " stroke-dasharray=\"6 4\""),
+                (false, false) => ("#bbbbbb", " stroke-dasharray=\"6 4\" stroke-opacity=\"0.5\""),
+            };
+
+            // Links that do not move rightwards (recurrent or same-column) are drawn as arcs.
+            let path = if to.0 > from.0 {
+                format!("M {:.1} {:.1} L {:.1} {:.1}", from.0, from.1, to.0, to.1)
+            } else {
+                let lift = COLUMN_SPACING / 2.0 + (from.1 - to.1).abs() / 4.0;
+                format!(
+                    "M {:.1} {:.1} Q {:.1} {:.1} {:.1} {:.1}",
+                    from.0, from.1, (from.0 + to.0) / 2.0, from.1.min(to.1) - lift, to.0, to.1
+                )
+            };
+            writeln!(
+                writer,
+                "  <path d=\"{}\" stroke=\"{}\" stroke-width=\"{:.2}\"{}><title>#{} {:.3}</title></path>",
+                path, color, 0.75 + 3.25 * magnitude, style, connection.get_innovation_number(), weight
+            )?;
+        }
+        writeln!(writer, "</g>")?;
+
+        writeln!(writer, "<g class=\"neurons\">")?;
+        for layer in layers {
+            for &id in layer {
+                let (neuron, &(x, y)) = match (self.get_neuron(id), position.get(&id)) {
+                    (Some(neuron), Some(position)) => (neuron, position),
+                    _ => continue
+                };
+                let role = Role::of(neuron);
+                writeln!(
+                    writer,
+                    "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"{}\" stroke=\"#333333\"/>",
+                    x, y, RADIUS, role_color(role)
+                )?;
+                if options.show_labels {
+                    let (dx, anchor) = match role {
+                        Role::Sensor => (-RADIUS - 6.0, "end"),
+                        Role::Output => (RADIUS + 6.0, "start"),
+                        Role::Hidden => (0.0, "middle"),
+                    };
+                    let dy = if role == Role::Hidden { -RADIUS - 6.0 } else { 4.0 };
+                    writeln!(
+                        writer,
+                        "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"{}\">{}</text>",
+                        x + dx, y + dy, anchor, layout.get_label(id)
+                    )?;
+                }
+            }
+        }
+        writeln!(writer, "</g>")?;
+
+        if options.show_legend {
+            let top = MARGIN + network_height + 20.0;
+            writeln!(writer, "<g class=\"legend\">")?;
+            let lines = [(POSITIVE, "positive", ""), (NEGATIVE, "negative", ""), (DISABLED, "disabled", " stroke-dasharray=\"6 4\"")];
+            for (index, (color, label, style)) in lines.iter().enumerate() {
+                let x = MARGIN + index as f64 * 110.0;
+                writeln!(
+                    writer,
+                    "  <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"2\"{}/>",
+                    x, top, x + 30.0, top, color, style
+                )?;
+                writeln!(writer, "  <text x=\"{:.1}\" y=\"{:.1}\">{}</text>", x + 36.0, top + 4.0, label)?;
+            }
+            for (index, (role, label)) in [(Role::Sensor, "sensor"), (Role::Hidden, "hidden"), (Role::Output, "output")].iter().enumerate() {
+                let x = MARGIN + index as f64 * 110.0;
+                let y = top + 30.0;
+                writeln!(
+                    writer,
+                    "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"7\" fill=\"{}\" stroke=\"#333333\"/>",
+                    x + 7.0, y, role_color(*role)
+                )?;
+                writeln!(writer, "  <text x=\"{:.1}\" y=\"{:.1}\">{}</text>", x + 20.0, y + 4.0, label)?;
+            }
+            writeln!(writer, "</g>")?;
+        }
+
+        writeln!(writer, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENOME: &str = "
+        neuron 0 sensor
+        neuron 1 sensor
+        neuron 2 output
+        neuron 3 hidden 100
+        connection 4 0 3 2 enabled
+        connection 5 3 2 -1 enabled
+        connection 6 1 2 0.5 disabled
+    ";
+
+    #[test]
+    fn test_to_svg() {
+        let genome: Genome = GENOME.parse().unwrap();
+        let svg = genome.to_svg();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<circle").count(), 4 + 3);
+        assert_eq!(svg.matches("<path").count(), 3);
+        assert!(svg.contains(">in1</text>"));
+        assert!(svg.contains(">out0</text>"));
+        assert!(svg.contains(">h3</text>"));
+        assert!(svg.contains(&format!("stroke=\"{}\" stroke-width=\"1.56\" stroke-dasharray=\"6 4\"", DISABLED)));
+        assert!(svg.contains("class=\"legend\""));
+
+        // Sensors, the hidden neuron and the output occupy three columns.
+        assert!(svg.contains("<circle cx=\"60.0\""));
+        assert!(svg.contains("<circle cx=\"200.0\""));
+        assert!(svg.contains("<circle cx=\"340.0\""));
+    }
+
+    #[test]
+    fn test_svg_options() {
+        let genome: Genome = GENOME.parse().unwrap();
+        let mut options = SvgOptions::new();
+        options.set_show_legend(false);
+        options.set_show_labels(false);
+        options.set_highlight_disabled(false);
+        let svg = genome.to_svg_with(&options);
+
+        assert!(!svg.contains("<text"));
+        assert!(!svg.contains("class=\"legend\""));
+        assert!(!svg.contains(DISABLED));
+        assert!(svg.contains("stroke-opacity=\"0.5\""));
+    }
+
+    #[test]
+    fn test_recurrent_arc() {
+        let genome: Genome = "
+            neuron 0 sensor
+            neuron 1 output
+            neuron 2 hidden 10
+            connection 3 0 2 1 enabled
+            connection 4 2 1 1 enabled
+            connection 5 1 2 1 enabled
+        ".parse().unwrap();
+        assert_eq!(genome.to_svg().matches(" Q ").count(), 1);
+    }
+}
@@ -0,0 +1,172 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::{ Neat, RunResult };
+use crate::genome::Genome;
+use crate::species::Species;
+
+pub trait Reporter {
+    fn start_generation(&mut self, _generation: usize) {}
+
+    fn post_evaluate(&mut self, _neat: &Neat) {}
+
+    fn species_created(&mut self, _species: &Species, _generation: usize) {}
+
+    fn species_extinct(&mut self, _species: &Species, _generation: usize) {}
+
+    fn new_best(&mut self, _genome: &Genome, _fitness: f64, _generation: usize) {}
+
+    fn end_generation(&mut self, _neat: &Neat) {}
+
+    fn run_complete(&mut self, _result: &RunResult) {}
+}
+
+impl<R: Reporter> Reporter for Rc<RefCell<R>> {
+    fn start_generation(&mut self, generation: usize) {
+        self.borrow_mut().start_generation(generation);
+    }
+
+    fn post_evaluate(&mut self, neat: &Neat) {
+        self.borrow_mut().post_evaluate(neat);
+    }
+
+    fn species_created(&mut self, species: &Species, generation: usize) {
+        self.borrow_mut().species_created(species, generation);
+    }
+
+    fn species_extinct(&mut self, species: &Species, generation: usize) {
+        self.borrow_mut().species_extinct(species, generation);
+    }
+
+    fn new_best(&mut self, genome: &Genome, fitness: f64, generation: usize) {
+        self.borrow_mut().new_best(genome, fitness, generation);
+    }
+
+    fn end_generation(&mut self, neat: &Neat) {
+        self.borrow_mut().end_generation(neat);
+    }
+
+    fn run_complete(&mut self, result: &RunResult) {
+        self.borrow_mut().run_complete(result);
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StdoutReporter {
+    show_species_changes: bool,
+}
+
+impl StdoutReporter {
+    pub fn new() -> StdoutReporter {
+        StdoutReporter::default()
+    }
+
+    pub fn set_show_species_changes(&mut self, show_species_changes: bool) {
+        self.show_species_changes = show_species_changes;
+    }
+
+    pub fn summary(neat: &Neat) -> String {
+        let fitness = neat.get_fitness();
+        let n = fitness.len().max(1) as f64;
+        let best = fitness.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mean = fitness.iter().sum::<f64>() / n;
+        let stdev = (fitness.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+        let population = neat.iter_population().count().max(1) as f64;
+        let neurons = neat.iter_population().map(|genome| genome.iter_neurons().count()).sum::<usize>() as f64 / population;
+        let connections = neat.iter_population().map(|genome| genome.iter_connections().count()).sum::<usize>() as f64 / population;
+
+        format!(
+            "generation {:>4} | best {:>10.4} | mean {:>10.4} | stdev {:>10.4} | species {:>3} | neurons {:>6.2} | connections {:>6.2}",
+            neat.get_generation(), best, mean, stdev, neat.get_species().len(), neurons, connections
+        )
+    }
+}
+
+impl Reporter for StdoutReporter {
+    fn species_created(&mut self, species: &Species, generation: usize) {
+        if self.show_species_changes {
+            println!("generation {:>4} | species {} created", generation, species.get_id());
+        }
+    }
+
+    fn species_extinct(&mut self, species: &Species, generation: usize) {
+        if self.show_species_changes {
+            println!("generation {:>4} | species {} extinct", generation, species.get_id());
+        }
+    }
+
+    fn end_generation(&mut self, neat: &Neat) {
+        println!("{}", StdoutReporter::summary(neat));
+    }
+
+    fn run_complete(&mut self, result: &RunResult) {
+        println!(
+            "run complete: {} | best fitness {:.4} found in generation {}",
+            result.get_stop_reason(), result.get_fitness(), result.get_generation()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Criteria, GenomeConfig };
+
+    struct RecordingReporter(Rc<RefCell<Vec<String>>>);
+
+    impl Reporter for RecordingReporter {
+        fn start_generation(&mut self, generation: usize) {
+            self.0.borrow_mut().push(format!("start {}", generation));
+        }
+
+        fn post_evaluate(&mut self, neat: &Neat) {
+            self.0.borrow_mut().push(format!("evaluated {}", neat.get_fitness().len()));
+        }
+
+        fn species_created(&mut self, _species: &Species, _generation: usize) {
+            self.0.borrow_mut().push("created".to_string());
+        }
+
+        fn new_best(&mut self, _genome: &Genome, fitness: f64, _generation: usize) {
+            self.0.borrow_mut().push(format!("best {}", fitness));
+        }
+
+        fn end_generation(&mut self, neat: &Neat) {
+            self.0.borrow_mut().push(format!("end {}", neat.get_generation()));
+        }
+
+        fn run_complete(&mut self, result: &RunResult) {
+            self.0.borrow_mut().push(format!("complete {}", result.get_fitness()));
+        }
+    }
+
+    #[test]
+    fn test_reporter_events() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut neat = Neat::new(5, GenomeConfig::new(2, 1));
+        neat.add_reporter(RecordingReporter(Rc::clone(&log)));
+        neat.add_reporter(StdoutReporter::new());
+
+        let mut criteria = Criteria::new();
+        criteria.set_max_generations(2);
+        neat.run(&mut |_: &Genome| 1.0, criteria).unwrap();
+
+        let expected = vec![
+            "start 0", "created", "best 1", "evaluated 5", "end 0",
+            "start 1", "evaluated 5", "end 1",
+            "complete 1",
+        ];
+        assert_eq!(*log.borrow(), expected);
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut neat = Neat::new(4, GenomeConfig::new(2, 1));
+        neat.evaluate_population(&mut |_: &Genome| 2.0).unwrap();
+
+        let summary = StdoutReporter::summary(&neat);
+        assert!(summary.contains("best     2.0000"));
+        assert!(summary.contains("stdev     0.0000"));
+        assert!(summary.contains("neurons   3.00"));
+    }
+}
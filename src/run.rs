@@ -0,0 +1,312 @@
+use std::fmt;
+use std::time::{ Duration, Instant };
+use crate::{ ConfigError, Neat, NeatError, FitnessEvaluator };
+use crate::genome::Genome;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FitnessCriterion {
+    Max,
+    Mean,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StopReason {
+    FitnessThreshold,
+    MaxGenerations,
+    MaxEvaluations,
+    TimeLimit,
+    NoImprovement,
+    Predicate,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            StopReason::FitnessThreshold => "fitness threshold reached",
+            StopReason::MaxGenerations => "maximum number of generations reached",
+            StopReason::MaxEvaluations => "maximum number of evaluations reached",
+            StopReason::TimeLimit => "time limit reached",
+            StopReason::NoImprovement => "no improvement",
+            StopReason::Predicate => "stopped by predicate",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+type Predicate = Box<dyn FnMut(&Neat) -> bool>;
+
+#[derive(Default)]
+pub struct Criteria {
+    fitness_threshold: Option<(FitnessCriterion, f64)>,
+    max_generations: Option<usize>,
+    max_evaluations: Option<usize>,
+    time_limit: Option<Duration>,
+    no_improvement: Option<usize>,
+    predicate: Option<Predicate>,
+}
+
+impl Criteria {
+    pub fn new() -> Criteria {
+        Criteria::default()
+    }
+
+    pub fn set_fitness_threshold(&mut self, criterion: FitnessCriterion, threshold: f64) {
+        self.fitness_threshold = Some((criterion, threshold));
+    }
+
+    pub fn set_max_generations(&mut self, max_generations: usize) {
+        self.max_generations = Some(max_generations);
+    }
+
+    pub fn set_max_evaluations(&mut self, max_evaluations: usize) {
+        self.max_evaluations = Some(max_evaluations);
+    }
+
+    pub fn set_time_limit(&mut self, time_limit: Duration) {
+        self.time_limit = Some(time_limit);
+    }
+
+    pub fn set_no_improvement(&mut self, generations: usize) {
+        self.no_improvement = Some(generations);
+    }
+
+    pub fn set_predicate<P>(&mut self, predicate: P)
+    where
+        P: FnMut(&Neat) -> bool + 'static
+    {
+        self.predicate = Some(Box::new(predicate));
+    }
+
+    // A run needs at least one way to end, and a zero generation budget would
+    // still evaluate one generation before the first check.
+    fn validate(&self) -> Result<(), NeatError> {
+        if self.max_generations == Some(0) {
+            return Err(ConfigError::OutOfRange { name: "max_generations", value: 0.0, expected: "at least 1" }.into());
+        }
+        let bounded = self.fitness_threshold.is_some()
+            || self.max_generations.is_some()
+            || self.max_evaluations.is_some()
+            || self.time_limit.is_some()
+            || self.no_improvement.is_some()
+            || self.predicate.is_some();
+        if bounded {
+            Ok(())
+        } else {
+            Err(NeatError::NoStopCriterion)
+        }
+    }
+
+    fn check(&mut self, neat: &Neat, state: &RunState) -> Option<StopReason> {
+        if let Some((criterion, threshold)) = self.fitness_threshold {
+            let fitness = neat.get_fitness();
+            let value = match criterion {
+                FitnessCriterion::Max => fitness.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+                FitnessCriterion::Mean => fitness.iter().sum::<f64>() / fitness.len().max(1) as f64,
+            };
+            if value >= threshold {
+                return Some(StopReason::FitnessThreshold);
+            }
+        }
+        if let Some(max_generations) = self.max_generations {
+            if neat.get_generation() - state.first_generation + 1 >= max_generations {
+                return Some(StopReason::MaxGenerations);
+            }
+        }
+        if let Some(max_evaluations) = self.max_evaluations {
+            if state.evaluations >= max_evaluations {
+                return Some(StopReason::MaxEvaluations);
+            }
+        }
+        if let Some(time_limit) = self.time_limit {
+            if state.start.elapsed() >= time_limit {
+                return Some(StopReason::TimeLimit);
+            }
+        }
+        if let Some(generations) = self.no_improvement {
+            if neat.get_generation() - state.champion_generation >= generations {
+                return Some(StopReason::NoImprovement);
+            }
+        }
+        if let Some(predicate) = self.predicate.as_mut() {
+            if predicate(neat) {
+                return Some(StopReason::Predicate);
+            }
+        }
+        None
+    }
+}
+
+// Limits and the champion count from the generation the run started in, so a
+// resumed population gets the full budget again and earlier runs do not leak in.
+struct RunState {
+    start: Instant,
+    first_generation: usize,
+    evaluations: usize,
+    champion: Option<(Genome, f64)>,
+    champion_generation: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct RunResult {
+    champion: Genome,
+    fitness: f64,
+    generation: usize,
+    stop_reason: StopReason,
+}
+
+impl RunResult {
+    pub fn get_champion(&self) -> &Genome {
+        &self.champion
+    }
+
+    pub fn into_champion(self) -> Genome {
+        self.champion
+    }
+
+    pub fn get_fitness(&self) -> f64 {
+        self.fitness
+    }
+
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn get_stop_reason(&self) -> StopReason {
+        self.stop_reason
+    }
+}
+
+impl Neat {
+    pub fn run<E>(&mut self, evaluator: &mut E, mut criteria: Criteria) -> Result<RunResult, NeatError>
+    where
+        E: FitnessEvaluator + ?Sized
+    {
+        criteria.validate()?;
+        let mut state = RunState {
+            start: Instant::now(),
+            first_generation: self.generation,
+            evaluations: 0,
+            champion: None,
+            champion_generation: self.generation,
+        };
+
+        loop {
+            self.evaluate_population(evaluator)?;
+            state.evaluations += self.population_genome.len();
+
+            if let Some((index, fitness)) = self.find_best() {
+                let improved = match state.champion {
+                    Some((_, champion_fitness)) => fitness > champion_fitness,
+                    None => true
+                };
+                if improved {
+                    state.champion = Some((self.population_genome[index].clone(), fitness));
+                    state.champion_generation = self.generation;
+                }
+            }
+
+            if let Some(stop_reason) = criteria.check(self, &state) {
+                self.end_generation();
+
+                let (champion, fitness) = state.champion.ok_or(NeatError::EmptyPopulation)?;
+                let result = RunResult {
+                    champion,
+                    fitness,
+                    generation: state.champion_generation,
+                    stop_reason,
+                };
+                self.report(|reporter, _| reporter.run_complete(&result));
+                return Ok(result);
+            }
+
+            self.reproduce()?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GenomeConfig;
+
+    fn enabled_connections(genome: &Genome) -> f64 {
+        genome.iter_connections().filter(|connection| connection.is_enabled()).count() as f64
+    }
+
+    #[test]
+    fn test_run_max_generations() {
+        let mut config = GenomeConfig::new(2, 1);
+        config.set_is_connected(true);
+        let mut neat = Neat::new(20, config);
+
+        let mut criteria = Criteria::new();
+        criteria.set_max_generations(5);
+
+        let result = neat.run(&mut enabled_connections, criteria).unwrap();
+
+        assert_eq!(result.get_stop_reason(), StopReason::MaxGenerations);
+        assert_eq!(neat.get_generation(), 4);
+        assert!(result.get_generation() <= 4);
+        assert_eq!(result.get_fitness(), enabled_connections(result.get_champion()));
+
+        let mut criteria = Criteria::new();
+        criteria.set_max_generations(3);
+        let result = neat.run(&mut enabled_connections, criteria).unwrap();
+        assert_eq!(neat.get_generation(), 6);
+        assert!(result.get_generation() >= 4);
+    }
+
+    #[test]
+    fn test_run_fitness_threshold() {
+        let mut config = GenomeConfig::new(2, 1);
+        config.set_is_connected(true);
+        let mut neat = Neat::new(20, config);
+
+        let mut criteria = Criteria::new();
+        criteria.set_fitness_threshold(FitnessCriterion::Max, 2.0);
+        criteria.set_max_generations(100);
+
+        let result = neat.run(&mut enabled_connections, criteria).unwrap();
+
+        assert_eq!(result.get_stop_reason(), StopReason::FitnessThreshold);
+        assert!(result.get_fitness() >= 2.0);
+    }
+
+    #[test]
+    fn test_run_predicate_and_evaluations() {
+        let mut neat = Neat::new(10, GenomeConfig::new(2, 1));
+
+        let mut criteria = Criteria::new();
+        criteria.set_predicate(|neat: &Neat| neat.get_generation() == 2);
+        let result = neat.run(&mut |_: &Genome| 0.0, criteria).unwrap();
+        assert_eq!(result.get_stop_reason(), StopReason::Predicate);
+
+        let mut criteria = Criteria::new();
+        criteria.set_max_evaluations(25);
+        let result = neat.run(&mut |_: &Genome| 0.0, criteria).unwrap();
+        assert_eq!(result.get_stop_reason(), StopReason::MaxEvaluations);
+        assert_eq!(neat.get_generation(), 4);
+
+        let mut criteria = Criteria::new();
+        criteria.set_no_improvement(3);
+        let result = neat.run(&mut |_: &Genome| 0.0, criteria).unwrap();
+        assert_eq!(result.get_stop_reason(), StopReason::NoImprovement);
+        assert_eq!(result.get_generation(), 4);
+    }
+
+    #[test]
+    fn test_run_requires_a_limit() {
+        let mut neat = Neat::new(10, GenomeConfig::new(2, 1));
+
+        assert!(matches!(neat.run(&mut |_: &Genome| 0.0, Criteria::new()), Err(NeatError::NoStopCriterion)));
+
+        let mut criteria = Criteria::new();
+        criteria.set_max_generations(0);
+        assert!(matches!(
+            neat.run(&mut |_: &Genome| 0.0, criteria),
+            Err(NeatError::Config(ConfigError::OutOfRange { name: "max_generations", .. }))
+        ));
+        assert_eq!(neat.get_generation(), 0);
+        assert!(neat.get_fitness().is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::genome::Genome;
+
+#[derive(Clone, Debug)]
+pub struct Species {
+    id: usize,
+    representative: Genome,
+    members: Vec<usize>,
+    best_fitness: f64,
+    last_improved: usize,
+    created: usize,
+}
+
+impl Species {
+    pub fn new(id: usize, representative: Genome, generation: usize) -> Species {
+        Species {
+            id,
+            representative,
+            members: Vec::new(),
+            best_fitness: f64::NEG_INFINITY,
+            last_improved: generation,
+            created: generation,
+        }
+    }
+
+    pub(crate) fn from_parts(
+        id: usize,
+        representative: Genome,
+        members: Vec<usize>,
+        best_fitness: f64,
+        last_improved: usize,
+        created: usize
+    ) -> Species {
+        Species {
+            id,
+            representative,
+            members,
+            best_fitness,
+            last_improved,
+            created,
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+
+    pub fn get_representative(&self) -> &Genome {
+        &self.representative
+    }
+
+    pub fn set_representative(&mut self, representative: Genome) {
+        self.representative = representative;
+    }
+
+    pub fn get_members(&self) -> &[usize] {
+        &self.members
+    }
+
+    pub fn add_member(&mut self, index: usize) {
+        self.members.push(index);
+    }
+
+    pub fn clear_members(&mut self) {
+        self.members.clear();
+    }
+
+    pub fn get_best_fitness(&self) -> f64 {
+        self.best_fitness
+    }
+
+    pub fn get_last_improved(&self) -> usize {
+        self.last_improved
+    }
+
+    pub fn get_created(&self) -> usize {
+        self.created
+    }
+
+    pub fn update_fitness(&mut self, fitness: f64, generation: usize) {
+        if fitness > self.best_fitness {
+            self.best_fitness = fitness;
+            self.last_improved = generation;
+        }
+    }
+
+    pub fn is_stagnant(&self, generation: usize, max_stagnation: usize) -> bool {
+        generation.saturating_sub(self.last_improved) > max_stagnation
+    }
+}
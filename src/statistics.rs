@@ -0,0 +1,260 @@
+use std::fs::File;
+use std::io::{ self, BufWriter, Write };
+use std::path::Path;
+use crate::Neat;
+use crate::reporter::Reporter;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenerationStatistics {
+    generation: usize,
+    fitness_min: f64,
+    fitness_max: f64,
+    fitness_mean: f64,
+    fitness_median: f64,
+    species_sizes: Vec<usize>,
+    neurons_mean: f64,
+    neurons_max: usize,
+    connections_mean: f64,
+    connections_max: usize,
+    enabled_connections: usize,
+    disabled_connections: usize,
+    innovations: u32,
+    innovation_growth: u32,
+}
+
+impl GenerationStatistics {
+    pub fn from_neat(neat: &Neat, previous_innovations: u32) -> GenerationStatistics {
+        let mut fitness = neat.get_fitness().to_vec();
+        fitness.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let n_fitness = fitness.len();
+        let fitness_median = match n_fitness {
+            0 => f64::NAN,
+            n if n % 2 == 0 => (fitness[n / 2 - 1] + fitness[n / 2]) / 2.0,
+            n => fitness[n / 2],
+        };
+
+        let population = neat.iter_population().count().max(1) as f64;
+        let neurons: Vec<usize> = neat.iter_population().map(|genome| genome.iter_neurons().count()).collect();
+        let connections: Vec<usize> = neat.iter_population().map(|genome| genome.iter_connections().count()).collect();
+        let enabled_connections = neat.iter_population()
+            .map(|genome| genome.iter_connections().filter(|connection| connection.is_enabled()).count())
+            .sum::<usize>();
+        let innovations = neat.get_counter().get_innovation_count();
+
+        GenerationStatistics {
+            generation: neat.get_generation(),
+            fitness_min: fitness.first().cloned().unwrap_or(f64::NAN),
+            fitness_max: fitness.last().cloned().unwrap_or(f64::NAN),
+            fitness_mean: fitness.iter().sum::<f64>() / n_fitness as f64,
+            fitness_median,
+            species_sizes: neat.get_species().iter().map(|species| species.get_members().len()).collect(),
+            neurons_mean: neurons.iter().sum::<usize>() as f64 / population,
+            neurons_max: neurons.iter().cloned().max().unwrap_or(0),
+            connections_mean: connections.iter().sum::<usize>() as f64 / population,
+            connections_max: connections.iter().cloned().max().unwrap_or(0),
+            enabled_connections,
+            disabled_connections: connections.iter().sum::<usize>() - enabled_connections,
+            innovations,
+            innovation_growth: innovations.saturating_sub(previous_innovations),
+        }
+    }
+
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn get_fitness_min(&self) -> f64 {
+        self.fitness_min
+    }
+
+    pub fn get_fitness_max(&self) -> f64 {
+        self.fitness_max
+    }
+
+    pub fn get_fitness_mean(&self) -> f64 {
+        self.fitness_mean
+    }
+
+    pub fn get_fitness_median(&self) -> f64 {
+        self.fitness_median
+    }
+
+    pub fn get_species_count(&self) -> usize {
+        self.species_sizes.len()
+    }
+
+    pub fn get_species_sizes(&self) -> &[usize] {
+        &self.species_sizes
+    }
+
+    pub fn get_neurons_mean(&self) -> f64 {
+        self.neurons_mean
+    }
+
+    pub fn get_neurons_max(&self) -> usize {
+        self.neurons_max
+    }
+
+    pub fn get_connections_mean(&self) -> f64 {
+        self.connections_mean
+    }
+
+    pub fn get_connections_max(&self) -> usize {
+        self.connections_max
+    }
+
+    pub fn get_enabled_connections(&self) -> usize {
+        self.enabled_connections
+    }
+
+    pub fn get_disabled_connections(&self) -> usize {
+        self.disabled_connections
+    }
+
+    pub fn get_innovations(&self) -> u32 {
+        self.innovations
+    }
+
+    pub fn get_innovation_growth(&self) -> u32 {
+        self.innovation_growth
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StatisticsReporter {
+    generations: Vec<GenerationStatistics>,
+}
+
+impl StatisticsReporter {
+    pub fn new() -> StatisticsReporter {
+        StatisticsReporter::default()
+    }
+
+    pub fn get_generations(&self) -> &[GenerationStatistics] {
+        &self.generations
+    }
+
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "generation,fitness_min,fitness_max,fitness_mean,fitness_median,species_count,species_sizes,\
+             neurons_mean,neurons_max,connections_mean,connections_max,enabled_connections,disabled_connections,\
+             innovations,innovation_growth"
+        )?;
+        for stats in self.generations.iter() {
+            let species_sizes: Vec<String> = stats.species_sizes.iter().map(|size| size.to_string()).collect();
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                stats.generation, stats.fitness_min, stats.fitness_max, stats.fitness_mean, stats.fitness_median,
+                stats.species_sizes.len(), species_sizes.join(";"), stats.neurons_mean, stats.neurons_max,
+                stats.connections_mean, stats.connections_max, stats.enabled_connections,
+                stats.disabled_connections, stats.innovations, stats.innovation_growth
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "[")?;
+        for (i, stats) in self.generations.iter().enumerate() {
+            let species_sizes: Vec<String> = stats.species_sizes.iter().map(|size| size.to_string()).collect();
+            write!(
+                writer,
+                "  {{\"generation\": {}, \"fitness_min\": {}, \"fitness_max\": {}, \"fitness_mean\": {}, \
+                 \"fitness_median\": {}, \"species_count\": {}, \"species_sizes\": [{}], \"neurons_mean\": {}, \
+                 \"neurons_max\": {}, \"connections_mean\": {}, \"connections_max\": {}, \
+                 \"enabled_connections\": {}, \"disabled_connections\": {}, \"innovations\": {}, \
+                 \"innovation_growth\": {}}}",
+                stats.generation, json_number(stats.fitness_min), json_number(stats.fitness_max),
+                json_number(stats.fitness_mean), json_number(stats.fitness_median), stats.species_sizes.len(),
+                species_sizes.join(", "), json_number(stats.neurons_mean), stats.neurons_max,
+                json_number(stats.connections_mean), stats.connections_max, stats.enabled_connections,
+                stats.disabled_connections, stats.innovations, stats.innovation_growth
+            )?;
+            if i + 1 < self.generations.len() {
+                write!(writer, ",")?;
+            }
+            writeln!(writer)?;
+        }
+        writeln!(writer, "]")
+    }
+
+    pub fn save_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_json(&mut writer)?;
+        writer.flush()
+    }
+}
+
+impl Reporter for StatisticsReporter {
+    fn end_generation(&mut self, neat: &Neat) {
+        let previous = self.generations.last().map_or(0, |stats| stats.innovations);
+        self.generations.push(GenerationStatistics::from_neat(neat, previous));
+    }
+}
+
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::{ Criteria, GenomeConfig };
+    use crate::genome::Genome;
+
+    #[test]
+    fn test_statistics_reporter() {
+        let mut config = GenomeConfig::new(2, 1);
+        config.set_is_connected(true);
+        let mut neat = Neat::new(4, config);
+        let stats = Rc::new(RefCell::new(StatisticsReporter::new()));
+        neat.add_reporter(Rc::clone(&stats));
+
+        let mut fitness = vec![4.0, 1.0, 3.0, 2.0].into_iter().cycle();
+        let mut criteria = Criteria::new();
+        criteria.set_max_generations(3);
+        neat.run(&mut |_: &Genome| fitness.next().unwrap(), criteria).unwrap();
+
+        let stats = stats.borrow();
+        let generations = stats.get_generations();
+        assert_eq!(generations.len(), 3);
+
+        let first = &generations[0];
+        assert_eq!(first.get_generation(), 0);
+        assert_eq!(first.get_fitness_min(), 1.0);
+        assert_eq!(first.get_fitness_max(), 4.0);
+        assert_eq!(first.get_fitness_mean(), 2.5);
+        assert_eq!(first.get_fitness_median(), 2.5);
+        assert_eq!(first.get_species_sizes().iter().sum::<usize>(), 4);
+        assert_eq!(first.get_neurons_mean(), 3.0);
+        assert_eq!(first.get_enabled_connections() + first.get_disabled_connections(), 8);
+        assert_eq!(first.get_innovations(), 5);
+        assert_eq!(first.get_innovation_growth(), 5);
+
+        let mut csv = Vec::new();
+        stats.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.lines().nth(1).unwrap().starts_with("0,1,4,2.5,2.5,"));
+
+        let mut json = Vec::new();
+        stats.write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.starts_with("[\n  {\"generation\": 0, \"fitness_min\": 1,"));
+        assert_eq!(json.matches("\"generation\"").count(), 3);
+    }
+}
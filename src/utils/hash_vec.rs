@@ -2,29 +2,39 @@
 
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt;
 use std::hash::Hash;
-use std::ops::{ Index, IndexMut };
+use std::iter::FromIterator;
+use std::iter::Zip;
+use std::ops::{ Bound, Index, IndexMut, RangeBounds };
 use std::slice::{ Iter, IterMut };
+use std::vec::IntoIter;
 
+#[derive(Clone)]
 pub struct HashVec<K, T>
 where
-    K: Eq + PartialEq + Hash + Copy,
+    K: Eq + PartialEq + PartialOrd + Hash + Copy,
     T: PartialOrd + Ord
 {
     max_key: Option<K>,
+    // Whether the keys are strictly ascending, which lets `range` binary search.
+    ascending: bool,
     set: HashMap<K, usize>,
+    keys: Vec<K>,
     data: Vec<T>,
 }
 
 impl<K, T> HashVec<K, T>
 where
-    K: Eq + PartialEq + Hash + Copy,
+    K: Eq + PartialEq + PartialOrd + Hash + Copy,
     T: PartialOrd + Ord
 {
     pub fn new() -> HashVec<K, T> {
         HashVec{
             max_key: None,
+            ascending: true,
             set: HashMap::new(),
+            keys: Vec::new(),
             data: Vec::new(),
         }
     }
@@ -33,6 +43,10 @@ where
         self.data.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
     pub fn insert(&mut self, key: K, item: T) -> usize {
         match self.set.get(&key) {
             Some(&index) => {
@@ -42,17 +56,12 @@ where
                 index
             },
             None => {
+                self.ascending &= self.keys.last().is_none_or(|last| *last < key);
                 self.data.push(item);
+                self.keys.push(key);
                 let index = self.data.len() - 1;
                 self.set.insert(key, index);
-                match self.max_key {
-                    None => self.max_key = Some(key),
-                    Some(key) => {
-                        if self.get(key).unwrap() < &self.data[index] {
-                            self.max_key = Some(key);
-                        }
-                    }
-                }
+                self.update_max_key(key, index);
                 index
             }
         }
@@ -60,7 +69,7 @@ where
 
     pub fn insert_ordered(&mut self, key: K, item: T) -> usize {
         if !self.data.is_sorted() {
-            self.data.sort();
+            self.sort();
         }
         match self.set.get(&key) {
             Some(&index) => {
@@ -80,20 +89,44 @@ where
                         _ => continue
                     }
                 }
+                self.ascending &= (index == 0 || self.keys[index - 1] < key)
+                    && self.keys.get(index).is_none_or(|next| key < *next);
                 self.data.insert(index, item);
-                match self.max_key {
-                    None => self.max_key = Some(key),
-                    Some(key) => {
-                        if self.get(key).unwrap() < &self.data[index] {
-                            self.max_key = Some(key);
-                        }
+                self.keys.insert(index, key);
+                for shifted in self.keys[index + 1..].iter() {
+                    if let Some(position) = self.set.get_mut(shifted) {
+                        *position += 1;
                     }
                 }
+                self.set.insert(key, index);
+                self.update_max_key(key, index);
                 index
             }
         }
     }
 
+    fn update_max_key(&mut self, key: K, index: usize) {
+        match self.max_key {
+            None => self.max_key = Some(key),
+            Some(max_key) => {
+                if self.get(max_key).unwrap() < &self.data[index] {
+                    self.max_key = Some(key);
+                }
+            }
+        }
+    }
+
+    fn sort(&mut self) {
+        let mut entries: Vec<(K, T)> = self.keys.drain(..).zip(self.data.drain(..)).collect();
+        entries.sort_by(|a, b| a.1.cmp(&b.1));
+        for (index, (key, item)) in entries.into_iter().enumerate() {
+            self.set.insert(key, index);
+            self.keys.push(key);
+            self.data.push(item);
+        }
+        self.ascending = self.keys.windows(2).all(|pair| pair[0] < pair[1]);
+    }
+
     pub fn get_max_key(&self) -> Option<K> {
         self.max_key
     }
@@ -103,19 +136,13 @@ where
     }
 
     pub fn get(&self, key: K) -> Option<&T> {
-        let index = match self.set.get(&key) {
-            Some(&index) => index,
-            None => return None
-        };
+        let index = self.set.get(&key)?;
 
-        Some(&self.data[index])
+        Some(&self.data[*index])
     }
 
     pub fn get_mut(&mut self, key: K) -> Option<&mut T> {
-        let index = match self.set.get(&key) {
-            Some(&index) => index,
-            None => return None
-        };
+        let index = *self.set.get(&key)?;
 
         Some(self.data.index_mut(index))
     }
@@ -136,18 +163,78 @@ where
         }
     }
 
-    pub fn iter(&self) -> Iter<T> {
+    pub fn get_key(&self, index: usize) -> Option<K> {
+        self.keys.get(index).copied()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
         self.data.iter()
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         self.data.iter_mut()
     }
+
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.keys.iter().copied()
+    }
+
+    pub fn iter_pairs(&self) -> impl Iterator<Item = (K, &T)> + '_ {
+        self.keys.iter().copied().zip(self.data.iter())
+    }
+
+    // Entries whose keys fall in the range, in storage order. Collections filled with
+    // `insert_ordered` and keyed by the ordering field keep their keys ascending, so the
+    // bounds are found by binary search; any other order falls back to a linear filter.
+    pub fn range<'a, R>(&'a self, range: R) -> impl Iterator<Item = (K, &'a T)> + 'a
+    where
+        R: RangeBounds<K> + 'a
+    {
+        let (start, end) = if self.ascending {
+            let start = match range.start_bound() {
+                Bound::Included(bound) => self.keys.partition_point(|key| key < bound),
+                Bound::Excluded(bound) => self.keys.partition_point(|key| key <= bound),
+                Bound::Unbounded => 0,
+            };
+            let end = match range.end_bound() {
+                Bound::Included(bound) => self.keys.partition_point(|key| key <= bound),
+                Bound::Excluded(bound) => self.keys.partition_point(|key| key < bound),
+                Bound::Unbounded => self.keys.len(),
+            };
+            (start, end.max(start))
+        } else {
+            (0, self.keys.len())
+        };
+        let ascending = self.ascending;
+        self.keys[start..end].iter().copied()
+            .zip(self.data[start..end].iter())
+            .filter(move |(key, _)| ascending || range.contains(key))
+    }
+}
+
+impl<K, T> Default for HashVec<K, T>
+where
+    K: Eq + PartialEq + PartialOrd + Hash + Copy,
+    T: PartialOrd + Ord
+{
+    fn default() -> HashVec<K, T> {
+        HashVec::new()
+    }
+}
+
+impl<K, T> fmt::Debug for HashVec<K, T>
+where
+    K: Eq + PartialEq + PartialOrd + Hash + Copy + fmt::Debug,
+    T: PartialOrd + Ord + fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter_pairs()).finish()
+    }
 }
 
 impl<K, T> Index<usize> for HashVec<K, T>
 where
-    K: Eq + PartialEq + Hash + Copy,
+    K: Eq + PartialEq + PartialOrd + Hash + Copy,
     T: PartialOrd + Ord
 {
     type Output = T;
@@ -159,7 +246,7 @@ where
 
 impl<K, T> IndexMut<usize> for HashVec<K, T>
 where
-    K: Eq + PartialEq + Hash + Copy,
+    K: Eq + PartialEq + PartialOrd + Hash + Copy,
     T: PartialOrd + Ord
 {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
@@ -167,6 +254,69 @@ where
     }
 }
 
+impl<K, T> IntoIterator for HashVec<K, T>
+where
+    K: Eq + PartialEq + PartialOrd + Hash + Copy,
+    T: PartialOrd + Ord
+{
+    type Item = (K, T);
+    type IntoIter = Zip<IntoIter<K>, IntoIter<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.into_iter().zip(self.data)
+    }
+}
+
+impl<'a, K, T> IntoIterator for &'a HashVec<K, T>
+where
+    K: Eq + PartialEq + PartialOrd + Hash + Copy,
+    T: PartialOrd + Ord
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+impl<'a, K, T> IntoIterator for &'a mut HashVec<K, T>
+where
+    K: Eq + PartialEq + PartialOrd + Hash + Copy,
+    T: PartialOrd + Ord
+{
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter_mut()
+    }
+}
+
+impl<K, T> FromIterator<(K, T)> for HashVec<K, T>
+where
+    K: Eq + PartialEq + PartialOrd + Hash + Copy,
+    T: PartialOrd + Ord
+{
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(iter: I) -> HashVec<K, T> {
+        let mut hv = HashVec::new();
+        hv.extend(iter);
+        hv
+    }
+}
+
+impl<K, T> Extend<(K, T)> for HashVec<K, T>
+where
+    K: Eq + PartialEq + PartialOrd + Hash + Copy,
+    T: PartialOrd + Ord
+{
+    fn extend<I: IntoIterator<Item = (K, T)>>(&mut self, iter: I) {
+        for (key, item) in iter {
+            self.insert(key, item);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,7 +336,7 @@ mod tests {
     #[test]
     fn test_get() {
         let mut hv: HashVec<usize, usize> = HashVec::new();
-        
+
         let keys = &[1, 2, 3];
         let values = &[7, 8, 9];
 
@@ -214,5 +364,58 @@ mod tests {
         for (&i1, &i2) in hv.iter().zip(values.iter()) {
             assert_eq!(i1, i2);
         }
+
+        assert_eq!(hv.get(0), Some(&9));
+        assert_eq!(hv.get(2), Some(&7));
+        assert_eq!(hv.get_max_key(), Some(0));
+    }
+
+    #[test]
+    fn test_collection_traits() {
+        let hv: HashVec<u32, u32> = vec![(3, 30), (1, 10), (2, 20)].into_iter().collect();
+
+        assert_eq!(hv.keys().collect::<Vec<_>>(), vec![3, 1, 2]);
+        assert_eq!(hv.iter_pairs().collect::<Vec<_>>(), vec![(3, &30), (1, &10), (2, &20)]);
+
+        let mut cloned = hv.clone();
+        cloned.extend(vec![(4, 40)]);
+        for value in &mut cloned {
+            *value += 1;
+        }
+
+        assert_eq!(hv.len(), 3);
+        assert_eq!((&cloned).into_iter().copied().collect::<Vec<_>>(), vec![31, 11, 21, 41]);
+        let pairs: Vec<(u32, u32)> = cloned.clone().into_iter().collect();
+        assert_eq!(pairs, vec![(3, 31), (1, 11), (2, 21), (4, 41)]);
+        assert_eq!(pairs.into_iter().collect::<HashVec<u32, u32>>().iter_pairs().collect::<Vec<_>>(), cloned.iter_pairs().collect::<Vec<_>>());
+        assert_eq!(format!("{:?}", hv), "{3: 30, 1: 10, 2: 20}");
+        assert!(HashVec::<u32, u32>::default().is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_range() {
+        let mut hv: HashVec<u32, u32> = HashVec::new();
+        for &key in &[5, 1, 4, 2, 3] {
+            hv.insert_ordered(key, key * 10);
+        }
+
+        assert_eq!(hv.range(2..4).collect::<Vec<_>>(), vec![(2, &20), (3, &30)]);
+        assert_eq!(hv.range(4..).map(|(key, _)| key).collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(hv.range(..=2).map(|(key, _)| key).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(hv.range((Bound::Excluded(1), Bound::Included(3))).map(|(key, _)| key).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(hv.range((Bound::Included(4), Bound::Excluded(2))).count(), 0);
+        assert_eq!(hv.range(6..).count(), 0);
+        // Keys that are not ascending are filtered in storage order instead.
+        let mut unsorted: HashVec<u32, u32> = HashVec::new();
+        for &key in &[3, 1, 4, 2] {
+            unsorted.insert(key, key * 10);
+        }
+        assert_eq!(unsorted.range(2..4).map(|(key, _)| key).collect::<Vec<_>>(), vec![3, 2]);
+
+        let mut misordered: HashVec<u32, u32> = HashVec::new();
+        misordered.insert_ordered(1, 50);
+        misordered.insert_ordered(2, 10);
+        misordered.insert_ordered(3, 30);
+        assert_eq!(misordered.range(1..3).map(|(key, _)| key).collect::<Vec<_>>(), vec![2, 1]);
+    }
+}
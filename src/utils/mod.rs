@@ -1,3 +1,7 @@
 pub mod hash_vec;
+pub mod rng;
+#[cfg(feature = "serde")]
+pub mod serde_innovation_map;
 
-pub use hash_vec::HashVec;
\ No newline at end of file
+pub use hash_vec::HashVec;
+pub use rng::NeatRng;
@@ -0,0 +1,87 @@
+use rand::{ Error, RngCore, SeedableRng };
+
+// xoshiro256** with an exposed state, so a run can be checkpointed and
+// resumed with the exact same random stream.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeatRng {
+    state: [u64; 4],
+}
+
+impl NeatRng {
+    pub fn from_state(state: [u64; 4]) -> NeatRng {
+        if state == [0; 4] {
+            NeatRng::seed_from_u64(0)
+        } else {
+            NeatRng { state }
+        }
+    }
+
+    pub fn get_state(&self) -> [u64; 4] {
+        self.state
+    }
+}
+
+impl RngCore for NeatRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for NeatRng {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: [u8; 32]) -> NeatRng {
+        let mut state = [0; 4];
+        for (word, bytes) in state.iter_mut().zip(seed.chunks(8)) {
+            let mut buffer = [0; 8];
+            buffer.copy_from_slice(bytes);
+            *word = u64::from_le_bytes(buffer);
+        }
+        NeatRng::from_state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_state_round_trip() {
+        let mut rng = NeatRng::seed_from_u64(42);
+        rng.gen::<f64>();
+
+        let mut restored = NeatRng::from_state(rng.get_state());
+        let expected: Vec<u64> = (0..10).map(|_| rng.gen()).collect();
+        let actual: Vec<u64> = (0..10).map(|_| restored.gen()).collect();
+
+        assert_eq!(expected, actual);
+        assert_ne!(NeatRng::from_state([0; 4]).get_state(), [0; 4]);
+    }
+}
@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+use serde::{ Deserialize, Deserializer, Serializer };
+use serde::ser::SerializeSeq;
+
+// Tuple keys can't be map keys in most formats (JSON among them), so the
+// innovation map goes over the wire as a sorted list of (in, out, innovation).
+pub fn serialize<S>(map: &HashMap<(u32, u32), u32>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer
+{
+    let mut entries: Vec<(u32, u32, u32)> = map.iter()
+        .map(|(&(neuron_in, neuron_out), &innovation)| (neuron_in, neuron_out, innovation))
+        .collect();
+    entries.sort();
+
+    let mut seq = serializer.serialize_seq(Some(entries.len()))?;
+    for entry in entries.iter() {
+        seq.serialize_element(entry)?;
+    }
+    seq.end()
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<(u32, u32), u32>, D::Error>
+where
+    D: Deserializer<'de>
+{
+    let entries: Vec<(u32, u32, u32)> = Vec::deserialize(deserializer)?;
+    Ok(entries.into_iter()
+        .map(|(neuron_in, neuron_out, innovation)| ((neuron_in, neuron_out), innovation))
+        .collect())
+}